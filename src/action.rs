@@ -12,6 +12,8 @@ use sc2_proto::{
 	raw::{ActionRawUnitCommand_oneof_target as ProtoTarget, ActionRaw_oneof_action as ProtoRawAction},
 	sc2api::{Action as ProtoAction, ActionChat_Channel, ActionError as ProtoActionError},
 };
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 // pub(crate) type Command = (u64, (AbilityId, Target, bool));
 
@@ -19,10 +21,12 @@ use sc2_proto::{
 pub(crate) struct Commander {
 	pub commands: FxHashMap<(AbilityId, Target, bool), Vec<u64>>,
 	pub autocast: FxHashMap<AbilityId, Vec<u64>>,
+	pub autocast_state: FxHashMap<u64, FxHashMap<AbilityId, bool>>,
 }
 
 /// Target of ability used by unit.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Target {
 	/// Ability target is position (move, build, ...).
 	Pos(Point2),
@@ -31,6 +35,37 @@ pub enum Target {
 	/// Ability don't require target (train, morph, research, ...).
 	None,
 }
+impl Target {
+	/// Checks if target is position.
+	#[inline]
+	pub fn is_pos(self) -> bool {
+		matches!(self, Target::Pos(_))
+	}
+	/// Checks if target is unit.
+	#[inline]
+	pub fn is_tag(self) -> bool {
+		matches!(self, Target::Tag(_))
+	}
+	/// Checks if there's no target.
+	#[inline]
+	pub fn is_none(self) -> bool {
+		matches!(self, Target::None)
+	}
+	/// Returns position if target is position.
+	pub fn as_pos(self) -> Option<Point2> {
+		match self {
+			Target::Pos(pos) => Some(pos),
+			_ => None,
+		}
+	}
+	/// Returns unit tag if target is unit.
+	pub fn as_tag(self) -> Option<u64> {
+		match self {
+			Target::Tag(tag) => Some(tag),
+			_ => None,
+		}
+	}
+}
 
 #[doc(hidden)]
 #[derive(Debug, Clone)]
@@ -130,6 +165,7 @@ impl FromProto<&ProtoAction> for Option<Action> {
 /// Structure used to analyze actions failed on previous game step.
 /// Stored in [`state.action_errors`](crate::game_state::GameState::action_errors).
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ActionError {
 	/// Tag of unit that was executing action.
 	pub unit: u64,
@@ -154,6 +190,7 @@ impl FromProto<&ProtoActionError> for ActionError {
 /// Result of executed action.
 #[allow(missing_docs)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ActionResult {
 	Success,
 	NotSupported,
@@ -2,22 +2,81 @@
 
 use crate::{
 	bot::{Locked, Rl},
-	client::{SC2Result, WS},
+	client::{SC2Error, WS},
 };
 use protobuf::Message;
 use sc2_proto::sc2api::{Request, Response};
-use tungstenite::Message::Binary;
+use std::{io, time::Duration};
+use tungstenite::{connect, stream::MaybeTlsStream, Message::Binary};
+
+fn read_timeout(ws: &WS) -> io::Result<Option<Duration>> {
+	match ws.get_ref() {
+		MaybeTlsStream::Plain(stream) => stream.read_timeout(),
+		_ => Ok(None),
+	}
+}
+fn set_read_timeout(ws: &WS, timeout: Option<Duration>) -> io::Result<()> {
+	match ws.get_ref() {
+		MaybeTlsStream::Plain(stream) => stream.set_read_timeout(timeout),
+		_ => Ok(()),
+	}
+}
 
 /// SC2 API. Can be accessed through [`self.api()`](crate::bot::Bot::api).
-pub struct API(Rl<WS>);
+pub struct API {
+	ws: Rl<WS>,
+	host: String,
+	port: i32,
+}
 impl API {
-	pub(crate) fn new(ws: WS) -> API {
-		API(Rl::new(ws))
+	pub(crate) fn new(ws: WS, host: &str, port: i32) -> API {
+		API {
+			ws: Rl::new(ws),
+			host: host.to_string(),
+			port,
+		}
+	}
+
+	/// Host and port of SC2 client this API is connected to.
+	pub fn address(&self) -> (&str, i32) {
+		(&self.host, self.port)
+	}
+
+	/// Sets maximum time to wait for a response from SC2 client,
+	/// `None` means waiting indefinitely (default).
+	///
+	/// When time is out, requests return [`SC2Error::Timeout`], after that
+	/// responses may be out of sync with requests, so it's better to leave the game.
+	///
+	/// Passing zero duration results in error.
+	pub fn set_timeout(&self, timeout: Option<Duration>) -> Result<(), SC2Error> {
+		set_read_timeout(&self.ws.read_lock(), timeout)?;
+		Ok(())
+	}
+	/// Returns timeout set by [`set_timeout`](Self::set_timeout).
+	pub fn timeout(&self) -> Option<Duration> {
+		read_timeout(&self.ws.read_lock()).ok().flatten()
+	}
+
+	/// Opens new websocket connection to SC2 client on given host and port,
+	/// replacing the current one. Timeout of the old connection is preserved.
+	///
+	/// Game is not affected by dropped connection, so after successful reconnect
+	/// it's enough to request new observation to continue playing.
+	pub fn reconnect(&mut self, host: &str, port: i32) -> Result<(), SC2Error> {
+		let timeout = self.timeout();
+		let url = format!("ws://{}:{}/sc2api", host, port);
+		let (ws, _rs) = connect(url)?;
+		set_read_timeout(&ws, timeout)?;
+		self.ws = Rl::new(ws);
+		self.host = host.to_string();
+		self.port = port;
+		Ok(())
 	}
 
 	/// Sends request and returns a response.
-	pub fn send(&self, req: Request) -> SC2Result<Response> {
-		let mut ws = self.0.write_lock();
+	pub fn send(&self, req: Request) -> Result<Response, SC2Error> {
+		let mut ws = self.ws.write_lock();
 
 		ws.write_message(Binary(req.write_to_bytes()?))?;
 
@@ -29,8 +88,8 @@ impl API {
 	}
 
 	/// Sends request, waits for the response, but ignores it (useful when response is empty).
-	pub fn send_request(&self, req: Request) -> SC2Result<()> {
-		let mut ws = self.0.write_lock();
+	pub fn send_request(&self, req: Request) -> Result<(), SC2Error> {
+		let mut ws = self.ws.write_lock();
 		ws.write_message(Binary(req.write_to_bytes()?))?;
 		let _ = ws.read_message()?;
 		Ok(())
@@ -41,15 +100,17 @@ impl API {
 	///
 	/// [`send`]: Self::send
 	/// [`send_request`]: Self::send_request
-	pub fn send_only(&self, req: Request) -> SC2Result<()> {
-		self.0.write_lock().write_message(Binary(req.write_to_bytes()?))?;
+	pub fn send_only(&self, req: Request) -> Result<(), SC2Error> {
+		self.ws
+			.write_lock()
+			.write_message(Binary(req.write_to_bytes()?))?;
 		Ok(())
 	}
 	/// Waits for a response (useful only after [`send_only`]).
 	///
 	/// [`send_only`]: Self::send_only
-	pub fn wait_response(&self) -> SC2Result<Response> {
-		let msg = self.0.write_lock().read_message()?;
+	pub fn wait_response(&self) -> Result<Response, SC2Error> {
+		let msg = self.ws.write_lock().read_message()?;
 
 		let mut res = Response::new();
 		res.merge_from_bytes(msg.into_data().as_slice())?;
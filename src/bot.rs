@@ -3,8 +3,12 @@
 use crate::{
 	action::{Action, ActionResult, Commander, Target},
 	api::API,
-	client::SC2Result,
-	consts::{RaceValues, FRAMES_PER_SECOND, INHIBITOR_IDS, RACE_VALUES, TECH_ALIAS, UNIT_ALIAS},
+	client::{SC2Error, SC2Result},
+	combat::{self, CombatResult},
+	consts::{
+		RaceValues, ALL_PRODUCERS, FRAMES_PER_SECOND, INHIBITOR_IDS, LAND_ABILITIES, PYLON_POWER_RADIUS,
+		RACE_VALUES, TECH_ALIAS, UNIT_ALIAS, WARPGATE_ABILITIES, WARP_PRISM_POWER_RADIUS,
+	},
 	debug::{DebugCommand, Debugger},
 	distance::*,
 	game_data::{Cost, GameData},
@@ -12,29 +16,48 @@ use crate::{
 	game_state::Effect,
 	game_state::{Alliance, GameState},
 	geometry::{Point2, Point3},
-	ids::{AbilityId, EffectId, UnitTypeId, UpgradeId},
-	player::Race,
+	ids::{AbilityId, BuffId, EffectId, UnitTypeId, UpgradeId},
+	pixel_map::{Pixel, PixelMap},
+	player::{GameResult, Race},
 	ramp::{Ramp, Ramps},
-	unit::{DataForUnit, SharedUnitData, Unit},
+	unit::{footprint_tiles, DataForUnit, PassengerUnit, SharedUnitData, Unit, UnitSnapshot},
 	units::{AllUnits, Units},
-	utils::{dbscan, range_query},
+	utils::{astar, dbscan, distance_field, range_query, CacheMap},
 	FromProto, IntoProto,
 };
 use indexmap::IndexSet;
-use num_traits::ToPrimitive;
+use ndarray::Array2;
+use num_traits::{FromPrimitive, ToPrimitive};
 use rand::prelude::*;
 use rustc_hash::{FxHashMap, FxHashSet, FxHasher};
 use sc2_proto::{
-	query::{RequestQueryBuildingPlacement, RequestQueryPathing},
-	sc2api::Request,
+	query::{RequestQueryAvailableAbilities, RequestQueryBuildingPlacement, RequestQueryPathing},
+	sc2api::{Request, Response},
 };
-use std::{fmt, hash::BuildHasherDefault, process::Child};
+use std::{collections::VecDeque, fmt, hash::BuildHasherDefault, process::Child, time::Duration};
 
 type FxIndexSet<T> = IndexSet<T, BuildHasherDefault<FxHasher>>;
 
+/// Number of steps positions of own units are tracked for stuck detection.
+const STUCK_HISTORY_LEN: usize = 8;
+/// Unit is considered stuck if it moved less than this distance during tracked steps.
+const STUCK_DISTANCE: f32 = 0.25;
+/// Minimal difference of terrain heights between different ground levels (cliffs are ~16 apart).
+const HEIGHT_LEVEL_THRESHOLD: i32 = 8;
+/// Number of positions closest to target, which are queried for warp in placement in one request.
+const WARP_PLACEMENT_CANDIDATES: usize = 32;
+
 #[cfg(feature = "enemies_cache")]
 use crate::{consts::BURROWED_IDS, unit::DisplayType};
 
+#[cfg(feature = "serde")]
+use crate::{event_log::EventLog, Event};
+#[cfg(feature = "serde")]
+use std::path::Path;
+
+#[cfg(feature = "rayon")]
+use rayon::iter::ParallelIterator;
+
 #[cfg(feature = "parking_lot")]
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 #[cfg(all(not(feature = "parking_lot"), feature = "rayon"))]
@@ -396,6 +419,9 @@ pub struct Bot {
 	/// Actual race of your bot.
 	pub race: Race,
 	/// Requested race of your opponent.
+	///
+	/// If opponent is random, it's updated to actual race when first enemy unit is seen,
+	/// see [`is_enemy_race_confirmed`](Self::is_enemy_race_confirmed).
 	pub enemy_race: Race,
 	/// Your in-game id.
 	pub player_id: u32,
@@ -404,7 +430,11 @@ pub struct Bot {
 	/// Opponent id on ladder, filled in `--OpponentId`.
 	pub opponent_id: String,
 	actions: Vec<Action>,
-	commander: Rw<Commander>,
+	pub(crate) commander: Rw<Commander>,
+	/// Maximum number of actions sent to API per step, `None` means no limit.
+	/// Exceeding actions are dropped and can be checked with [`dropped_actions`](Self::dropped_actions).
+	pub action_limit: Option<usize>,
+	dropped_actions: Vec<Action>,
 	/// Debug API
 	pub debug: Debugger,
 	/// Information about map.
@@ -447,17 +477,24 @@ pub struct Bot {
 	/// Bot's starting location.
 	pub start_location: Point2,
 	/// Opponent's starting location.
+	///
+	/// On maps with more than 2 start locations it's a guess until opponent is scouted,
+	/// see [`possible_enemy_starts`](Self::possible_enemy_starts).
 	pub enemy_start: Point2,
 	/// Bot's resource center on start location.
 	pub start_center: Point2,
 	/// Opponents's resource center on start location.
 	pub enemy_start_center: Point2,
+	enemy_start_candidates: Vec<Point2>,
+	scouted_enemy_race: Option<Race>,
 	techlab_tags: Rw<FxHashSet<u64>>,
 	reactor_tags: Rw<FxHashSet<u64>>,
 	/// All expansions.
 	pub expansions: Vec<Expansion>,
 	max_cooldowns: Rw<FxHashMap<UnitTypeId, f32>>,
 	last_units_health: Rw<FxHashMap<u64, u32>>,
+	previous_units: FxHashMap<u64, UnitSnapshot>,
+	position_history: FxHashMap<u64, VecDeque<Point2>>,
 	/// Obstacles on map which block vision of ground units, but still pathable.
 	pub vision_blockers: Vec<Point2>,
 	/// Ramps on map.
@@ -466,6 +503,16 @@ pub struct Bot {
 	pub(crate) owned_tags: FxHashSet<u64>,
 	pub(crate) under_construction: FxHashSet<u64>,
 	pub(crate) available_frames: Rw<FxHashMap<u64, u32>>,
+	pub(crate) last_step_duration: Duration,
+	pub(crate) game_loop_delta: u32,
+	pub(crate) game_result: Option<GameResult>,
+	pathing_cache: CacheMap<((usize, usize), (usize, usize)), Option<f32>>,
+	pathing_queue: Vec<(Target, Point2)>,
+	occupancy_grid: PixelMap,
+	retreat_field: Array2<u32>,
+	pub(crate) unit_labels: FxHashMap<String, FxHashSet<u64>>,
+	#[cfg(feature = "serde")]
+	event_log: Option<EventLog>,
 }
 
 impl Bot {
@@ -486,6 +533,17 @@ impl Bot {
 	pub fn game_step(&self) -> u32 {
 		self.game_step.get_locked()
 	}
+	/// Returns time spent in last [`on_step`](crate::Player::on_step) call.
+	pub fn last_step_duration(&self) -> Duration {
+		self.last_step_duration
+	}
+	/// Returns number of game loops passed since previous step.
+	///
+	/// Can be bigger than [`game_step`](Self::game_step) in realtime, when some steps were skipped
+	/// because [`on_step`](crate::Player::on_step) took too long.
+	pub fn game_loop_delta(&self) -> u32 {
+		self.game_loop_delta
+	}
 	/// Constructs new [`CountOptions`], used to count units fast and easy.
 	///
 	/// # Examples
@@ -523,8 +581,51 @@ impl Bot {
 	pub fn enemy_counter(&self) -> CountOptions {
 		CountOptions::new(self, true)
 	}
+	/// Returns number of actions, which will be sent to API at the end of current step.
+	pub fn pending_action_count(&self) -> usize {
+		let commander = self.commander.read_lock();
+		self.actions.len() + commander.commands.len() + commander.autocast.len()
+	}
+	/// Returns actions dropped on last step because of [`action_limit`](Self::action_limit).
+	pub fn dropped_actions(&self) -> &[Action] {
+		&self.dropped_actions
+	}
+	/// Starts recording [`Event`]s with resources and supply snapshot of each step
+	/// to given file in JSON lines format (appends if file already exists).
+	///
+	/// Log is flushed periodically and at the end of the game. Disabled by default.
+	#[cfg(feature = "serde")]
+	pub fn enable_event_log<P: AsRef<Path>>(&mut self, path: P) -> SC2Result<()> {
+		self.flush_event_log()?;
+		self.event_log = Some(EventLog::open(path)?);
+		Ok(())
+	}
+	/// Stops recording events started with [`enable_event_log`](Self::enable_event_log).
+	#[cfg(feature = "serde")]
+	pub fn disable_event_log(&mut self) -> SC2Result<()> {
+		self.flush_event_log()?;
+		self.event_log = None;
+		Ok(())
+	}
+	#[cfg(feature = "serde")]
+	pub(crate) fn log_events(&mut self, events: &[Event]) -> SC2Result<()> {
+		if let Some(mut log) = self.event_log.take() {
+			let result = log.record(self, events);
+			self.event_log = Some(log);
+			result?;
+		}
+		Ok(())
+	}
+	#[cfg(feature = "serde")]
+	pub(crate) fn flush_event_log(&mut self) -> SC2Result<()> {
+		if let Some(log) = &mut self.event_log {
+			log.flush()?;
+		}
+		Ok(())
+	}
 	pub(crate) fn get_actions(&mut self) -> &[Action] {
 		let actions = &mut self.actions;
+		self.dropped_actions.clear();
 
 		let mut commander = self.commander.write_lock();
 
@@ -546,6 +647,15 @@ impl Bot {
 					.map(|(ability, units)| Action::ToggleAutocast(ability, units)),
 			);
 		}
+		if let Some(limit) = self.action_limit {
+			if actions.len() > limit {
+				self.dropped_actions = actions.split_off(limit);
+				warn!(
+					"Action limit exceeded: {} actions dropped",
+					self.dropped_actions.len()
+				);
+			}
+		}
 
 		actions
 	}
@@ -615,7 +725,12 @@ impl Bot {
 	}
 	/// Checks if bot has enough resources and supply to build given unit type.
 	pub fn can_afford(&self, unit: UnitTypeId, check_supply: bool) -> bool {
-		let cost = self.get_unit_cost(unit);
+		self.can_afford_cost(self.get_unit_cost(unit), check_supply)
+	}
+	/// Checks if bot has enough resources and supply to pay given cost.
+	///
+	/// Useful for custom abilities with known costs.
+	pub fn can_afford_cost(&self, cost: Cost, check_supply: bool) -> bool {
 		if self.minerals < cost.minerals || self.vespene < cost.vespene {
 			return false;
 		}
@@ -631,16 +746,42 @@ impl Bot {
 			.get(&upgrade)
 			.map_or_else(Default::default, |data| data.cost())
 	}
+	/// Returns total cost of building all given unit types (e.g. a build order).
+	///
+	/// Costs are taken from [`get_unit_cost`](Self::get_unit_cost).
+	pub fn total_cost(&self, items: &[UnitTypeId]) -> Cost {
+		items.iter().map(|unit| self.get_unit_cost(*unit)).sum()
+	}
+	/// Returns total cost of making all given upgrades.
+	pub fn total_upgrade_cost(&self, items: &[UpgradeId]) -> Cost {
+		items.iter().map(|upgrade| self.get_upgrade_cost(*upgrade)).sum()
+	}
 	/// Checks if bot has enough resources to make given upgrade.
 	pub fn can_afford_upgrade(&self, upgrade: UpgradeId) -> bool {
 		let cost = self.get_upgrade_cost(upgrade);
 		self.minerals >= cost.minerals && self.vespene >= cost.vespene
 	}
-	/*
-	fn can_afford_ability(&self, ability: AbilityId) -> bool {
-		unimplemented!()
+	/// Returns energy cost of given ability or `None` if ability doesn't cost energy.
+	pub fn ability_energy_cost(&self, ability: AbilityId) -> Option<u32> {
+		self.game_data.ability_energy_cost(ability)
+	}
+	/// Checks if given caster has enough energy and bot has enough resources to use given ability.
+	///
+	/// Energy costs are taken from [`ability_energy_cost`](Self::ability_energy_cost),
+	/// resource costs from units and upgrades produced by the ability.
+	/// Returns `false` if ability requires energy, but caster's energy is unknown (e.g. it's a snapshot).
+	pub fn can_afford_ability(&self, ability: AbilityId, caster: &Unit) -> bool {
+		if let Some(cost) = self.ability_energy_cost(ability) {
+			match caster.energy() {
+				Some(energy) if energy >= cost => {}
+				_ => return false,
+			}
+		}
+
+		self.game_data.ability_cost(ability).map_or(true, |cost| {
+			self.minerals >= cost.minerals && self.vespene >= cost.vespene
+		})
 	}
-	*/
 	/// Subtracts cost of given unit type from [`minerals`],
 	/// [`vespene`], [`supply_left`] and adds to [`supply_used`].
 	///
@@ -649,14 +790,11 @@ impl Bot {
 	/// [`supply_left`]: Self::supply_left
 	/// [`supply_used`]: Self::supply_used
 	pub fn subtract_resources(&mut self, unit: UnitTypeId, subtract_supply: bool) {
-		let cost = self.get_unit_cost(unit);
-		self.minerals = self.minerals.saturating_sub(cost.minerals);
-		self.vespene = self.vespene.saturating_sub(cost.vespene);
-		if subtract_supply {
-			let supply_cost = cost.supply as u32;
-			self.supply_used += supply_cost;
-			self.supply_left = self.supply_left.saturating_sub(supply_cost);
+		let mut cost = self.get_unit_cost(unit);
+		if !subtract_supply {
+			cost.supply = 0.0;
 		}
+		self.reserve(cost);
 	}
 	/// Subtracts cost of given upgrade from [`minerals`] and [`vespene`].
 	///
@@ -664,8 +802,25 @@ impl Bot {
 	/// [`vespene`]: Self::vespene
 	pub fn subtract_upgrade_cost(&mut self, upgrade: UpgradeId) {
 		let cost = self.get_upgrade_cost(upgrade);
+		self.reserve(cost);
+	}
+	/// Subtracts given cost from [`minerals`], [`vespene`], [`supply_left`] and adds to [`supply_used`].
+	///
+	/// Unified version of [`subtract_resources`](Self::subtract_resources)
+	/// and [`subtract_upgrade_cost`](Self::subtract_upgrade_cost),
+	/// which can be used to account for custom spendings.
+	/// Set `supply` of cost to `0` to not subtract supply.
+	///
+	/// [`minerals`]: Self::minerals
+	/// [`vespene`]: Self::vespene
+	/// [`supply_left`]: Self::supply_left
+	/// [`supply_used`]: Self::supply_used
+	pub fn reserve(&mut self, cost: Cost) {
 		self.minerals = self.minerals.saturating_sub(cost.minerals);
 		self.vespene = self.vespene.saturating_sub(cost.vespene);
+		let supply_cost = cost.supply as u32;
+		self.supply_used += supply_cost;
+		self.supply_left = self.supply_left.saturating_sub(supply_cost);
 	}
 	/// Checks if given upgrade is complete.
 	pub fn has_upgrade(&self, upgrade: UpgradeId) -> bool {
@@ -713,6 +868,257 @@ impl Bot {
 			})
 			.unwrap_or(0.0)
 	}
+	/// Returns all own ready production structures, which have no orders
+	/// (2 orders are allowed for buildings with reactor).
+	pub fn idle_producers(&self) -> Units {
+		self.units
+			.my
+			.structures
+			.filter(|s| s.type_id().is_production_structure() && s.is_ready() && s.is_unused())
+	}
+	/// Returns own ready units and structures (e.g. larva or barracks), which can train given unit now
+	/// and have no orders (2 orders are allowed for buildings with reactor).
+	///
+	/// Producers are taken from [`ALL_PRODUCERS`] excluding workers,
+	/// buildings without techlab are excluded if unit requires it.
+	pub fn idle_producers_for(&self, unit: UnitTypeId) -> Units {
+		let producers = match ALL_PRODUCERS.get(&unit) {
+			Some(producers) => producers,
+			None => return Units::new(),
+		};
+		let require_techlab = self
+			.game_data
+			.units
+			.get(&unit)
+			.map_or(false, |data| data.require_attached);
+
+		self.units.my.all.filter(|u| {
+			!u.is_worker()
+				&& producers.contains(&u.type_id())
+				&& u.is_ready()
+				&& u.is_unused()
+				&& (!require_techlab || u.has_techlab())
+		})
+	}
+	/// Finds idle producer for given unit and orders it to train the unit.
+	///
+	/// Gateway units are warped in when ready warp gates exist,
+	/// at position closest to `warp_near` (start location if `None`),
+	/// see [`find_warp_placement`](Self::find_warp_placement).
+	/// Resources and supply are subtracted on success.
+	///
+	/// Unlike simple `train(unit, queue)`, takes extra `warp_near` argument,
+	/// because good warp-in position depends on the bot's strategy.
+	///
+	/// Producers already commanded on this step are skipped,
+	/// so it can be called in a loop to train several units at once:
+	/// ```
+	/// while self.train_from_any(UnitTypeId::Zealot, None, false) {}
+	/// ```
+	///
+	/// Returns `false` if bot can't afford the unit or there's no suitable producer.
+	pub fn train_from_any(&mut self, unit: UnitTypeId, warp_near: Option<Point2>, queue: bool) -> bool {
+		if !self.can_afford(unit, true) {
+			return false;
+		}
+
+		let commanded = self.commanded_tags();
+		if let Some(&ability) = WARPGATE_ABILITIES.get(&unit) {
+			if let Some(gate) = self.units.my.structures.iter().find(|s| {
+				s.type_id() == UnitTypeId::WarpGate
+					&& s.is_ready() && s.has_ability(ability)
+					&& !commanded.contains(&s.tag())
+			}) {
+				let near = warp_near.unwrap_or(self.start_location);
+				if let Some(pos) = self.find_warp_placement(unit, near) {
+					gate.warp_in(unit, pos);
+					self.subtract_resources(unit, true);
+					return true;
+				}
+			}
+		}
+
+		let producer = self
+			.idle_producers_for(unit)
+			.iter()
+			.find(|u| u.type_id() != UnitTypeId::WarpGate && !commanded.contains(&u.tag()))
+			.map(|u| u.tag());
+		if let Some(producer) = producer.and_then(|tag| self.units.my.all.get(tag)) {
+			producer.train(unit, queue);
+			self.subtract_resources(unit, true);
+			return true;
+		}
+		false
+	}
+	/// Tags of units, which were given commands on this step.
+	fn commanded_tags(&self) -> FxHashSet<u64> {
+		self.commander
+			.read_lock()
+			.commands
+			.values()
+			.flatten()
+			.copied()
+			.collect()
+	}
+	/// Target positions of warp-ins ordered on this step.
+	fn pending_warps(&self) -> Vec<Point2> {
+		self.commander
+			.read_lock()
+			.commands
+			.keys()
+			.filter_map(|(ability, target, _)| match target {
+				Target::Pos(pos) if WARPGATE_ABILITIES.values().any(|a| a == ability) => Some(*pos),
+				_ => None,
+			})
+			.collect()
+	}
+	fn upgrade_townhalls(&mut self, upgrade: UnitTypeId, requirement: &[UnitTypeId]) -> usize {
+		if !self
+			.units
+			.my
+			.structures
+			.iter()
+			.any(|s| requirement.contains(&s.type_id()) && s.is_ready())
+		{
+			return 0;
+		}
+
+		let townhalls = self
+			.units
+			.my
+			.townhalls
+			.filter(|t| t.type_id() == UnitTypeId::CommandCenter && t.is_ready() && t.is_idle());
+		let mut upgraded = 0;
+		for townhall in &townhalls {
+			if !self.can_afford(upgrade, false) {
+				break;
+			}
+			townhall.train(upgrade, false);
+			self.subtract_resources(upgrade, false);
+			upgraded += 1;
+		}
+		upgraded
+	}
+	/// Orders idle ready command centers to upgrade to orbital command while bot can afford it.
+	/// Does nothing if there's no ready barracks.
+	///
+	/// Returns number of command centers ordered to upgrade.
+	pub fn upgrade_townhalls_to_orbital(&mut self) -> usize {
+		self.upgrade_townhalls(
+			UnitTypeId::OrbitalCommand,
+			&[UnitTypeId::Barracks, UnitTypeId::BarracksFlying],
+		)
+	}
+	/// Orders idle ready command centers to upgrade to planetary fortress while bot can afford it.
+	/// Does nothing if there's no ready engineering bay.
+	///
+	/// Returns number of command centers ordered to upgrade.
+	pub fn upgrade_townhalls_to_planetary(&mut self) -> usize {
+		self.upgrade_townhalls(UnitTypeId::PlanetaryFortress, &[UnitTypeId::EngineeringBay])
+	}
+	/// Finds position closest to `near`, where given gateway unit can be warped in.
+	///
+	/// Only positions inside power fields of ready pylons and phasing warp prisms are checked.
+	/// Positions of warp-ins already ordered on this step are excluded.
+	/// Placement of closest candidates is validated with [`query_placement`](Self::query_placement)
+	/// in batches, next batch is queried only when all positions of the previous one are blocked.
+	///
+	/// Returns `None` if there's no free position in power fields or query failed.
+	pub fn find_warp_placement(&self, unit: UnitTypeId, near: Point2) -> Option<Point2> {
+		let ability = *WARPGATE_ABILITIES.get(&unit)?;
+
+		let pending = self.pending_warps();
+		let mut positions = self
+			.power_grid()
+			.indexed_iter()
+			.filter(|(_, p)| p.is_set())
+			.map(|((x, y), _)| Point2::from_tile(x, y))
+			.filter(|pos| self.is_pathable(*pos) && pending.iter().all(|p| p.is_further(1.0, *pos)))
+			.collect::<Vec<Point2>>();
+		positions.sort_unstable_by(|p1, p2| {
+			p1.distance_squared(near)
+				.partial_cmp(&p2.distance_squared(near))
+				.unwrap()
+		});
+
+		for batch in positions.chunks(WARP_PLACEMENT_CANDIDATES) {
+			let results = self
+				.query_placement(batch.iter().map(|pos| (ability, *pos, None)).collect(), false)
+				.ok()?;
+			if let Some((pos, _)) = batch
+				.iter()
+				.zip(results)
+				.find(|(_, res)| *res == ActionResult::Success)
+			{
+				return Some(*pos);
+			}
+		}
+		None
+	}
+	/// Returns time in seconds until the soonest in-progress unit or structure of given type is complete
+	/// or `None` if there's nothing of this type in progress.
+	///
+	/// Takes into account structures under construction and production orders,
+	/// including two parallel orders of buildings with reactor.
+	pub fn eta(&self, unit: UnitTypeId) -> Option<f32> {
+		let data = self.game_data.units.get(&unit)?;
+		let build_time = data.build_time;
+
+		let constructing = self
+			.units
+			.my
+			.all
+			.iter()
+			.filter(|u| u.type_id() == unit && !u.is_ready())
+			.map(|u| u.build_time_left());
+
+		let producing = data.ability.into_iter().flat_map(|ability| {
+			self.units
+				.my
+				.all
+				.iter()
+				.filter(|u| !u.is_worker())
+				.flat_map(move |u| {
+					let parallel = if u.has_reactor() { 2 } else { 1 };
+					u.orders()
+						.iter()
+						.take(parallel)
+						.filter(move |order| order.ability == ability)
+						.map(move |order| build_time * (1.0 - order.progress))
+				})
+		});
+
+		constructing
+			.chain(producing)
+			.min_by(|t1, t2| t1.partial_cmp(t2).unwrap())
+			.map(|loops| loops / FRAMES_PER_SECOND)
+	}
+	/// Orders all given units to execute the same command.
+	///
+	/// Tags are grouped under one command key, so they're sent to API as a single action.
+	pub fn command_group<'a, U>(&self, units: U, ability: AbilityId, target: Target, queue: bool)
+	where
+		U: IntoIterator<Item = &'a Unit>,
+	{
+		self.commander
+			.write_lock()
+			.commands
+			.entry((ability, target, queue))
+			.or_default()
+			.extend(units.into_iter().map(|u| u.tag()));
+	}
+	/// Runs given closure for every unit of the collection in parallel.
+	///
+	/// Giving commands to units inside the closure is safe, since they're collected
+	/// in shared commander behind `RwLock`. Although each command takes the lock,
+	/// so it's better to do heavy computations in parallel and command only when needed.
+	#[cfg(feature = "rayon")]
+	pub fn par_for_each_unit<F>(&self, units: &Units, f: F)
+	where
+		F: Fn(&Unit) + Sync + Send,
+	{
+		units.par_iter().for_each(f);
+	}
 	/// Move player camera to specified position.
 	pub fn move_camera(&mut self, pos: Point3) {
 		self.actions.push(Action::CameraMove(pos));
@@ -740,6 +1146,40 @@ impl Bot {
 			.copied()
 			.unwrap_or(0)
 	}
+	/// Returns difference of terrain heights on given positions (`a - b`).
+	pub fn height_difference<P1, P2>(&self, a: P1, b: P2) -> i32
+	where
+		P1: Into<(usize, usize)>,
+		P2: Into<(usize, usize)>,
+	{
+		self.get_height(a) as i32 - self.get_height(b) as i32
+	}
+	/// Checks if `attacker` position is on higher ground level than `target` position.
+	///
+	/// Ground units on lower level can't see units on higher level without vision from other source,
+	/// so they can't attack them.
+	pub fn is_on_high_ground<P1, P2>(&self, attacker: P1, target: P2) -> bool
+	where
+		P1: Into<(usize, usize)>,
+		P2: Into<(usize, usize)>,
+	{
+		self.height_difference(attacker, target) > HEIGHT_LEVEL_THRESHOLD
+	}
+	/// Checks if bot has vision of given unit, i.e. it's position is currently visible
+	/// and unit isn't cloaked or burrowed, or it's detected.
+	pub fn has_vision_of(&self, unit: &Unit) -> bool {
+		self.is_visible(unit.position()) && (!unit.is_cloaked() || unit.is_revealed())
+	}
+	/// Checks if ranged unit on `attacker` position is able to attack `target`.
+	///
+	/// Returns `false` when target is cloaked and not detected
+	/// or when it's on higher ground level and bot has no vision of it.
+	pub fn can_attack_from_to(&self, attacker: Point2, target: &Unit) -> bool {
+		if target.is_cloaked() && !target.is_revealed() {
+			return false;
+		}
+		!self.is_on_high_ground(target.position(), attacker) || self.has_vision_of(target)
+	}
 	/// Checks if it's possible to build on given position.
 	pub fn is_placeable<P: Into<(usize, usize)>>(&self, pos: P) -> bool {
 		self.game_info
@@ -747,6 +1187,57 @@ impl Bot {
 			.get(pos.into())
 			.map_or(false, |p| p.is_empty())
 	}
+	/// Returns grid where tiles occupied by own and currently visible enemy structures are set.
+	/// Flying structures are not included.
+	///
+	/// Updated each step.
+	pub fn occupancy_grid(&self) -> &PixelMap {
+		&self.occupancy_grid
+	}
+	/// Checks if building of given size (in tiles) can be placed on given position
+	/// using only [`placement_grid`](GameInfo::placement_grid) and [`occupancy_grid`](Self::occupancy_grid).
+	///
+	/// This check is cheap, but doesn't consider creep, psionic matrix and units,
+	/// use [`can_place`](Self::can_place) for exact check.
+	pub fn is_footprint_free(&self, pos: Point2, size: usize) -> bool {
+		footprint_tiles(pos, size).all(|tile| {
+			self.is_placeable(tile) && self.occupancy_grid.get(tile).map_or(false, |p| p.is_empty())
+		})
+	}
+	fn power_sources(&self) -> impl Iterator<Item = (Point2, f32)> + '_ {
+		self.units.my.all.iter().filter_map(|u| match u.type_id() {
+			UnitTypeId::Pylon if u.is_ready() => Some((u.position(), PYLON_POWER_RADIUS)),
+			UnitTypeId::WarpPrismPhasing => Some((u.position(), WARP_PRISM_POWER_RADIUS)),
+			_ => None,
+		})
+	}
+	/// Returns grid where tiles powered by own ready pylons and phased warp prisms are set.
+	///
+	/// Tile is considered powered if its center is inside of power field.
+	pub fn power_grid(&self) -> PixelMap {
+		let mut grid = PixelMap::from_elem(self.game_info.placement_grid.dim(), Pixel::Empty);
+		for (center, radius) in self.power_sources() {
+			let (cx, cy) = center.to_tile();
+			let r = radius.ceil() as usize;
+			for x in cx.saturating_sub(r)..=cx + r {
+				for y in cy.saturating_sub(r)..=cy + r {
+					if Point2::from_tile(x, y).is_closer(radius, center) {
+						if let Some(pixel) = grid.get_mut((x, y)) {
+							*pixel = Pixel::Set;
+						}
+					}
+				}
+			}
+		}
+		grid
+	}
+	/// Checks if given position is inside of power field of own ready pylon or phased warp prism.
+	///
+	/// Unlike [`is_powered`](Unit::is_powered) works for positions where nothing is built yet.
+	pub fn is_powered_at(&self, pos: Point2) -> bool {
+		self.power_sources()
+			.any(|(center, radius)| pos.is_closer(radius, center))
+	}
 	/// Checks if it's possible for ground units to walk through given position.
 	pub fn is_pathable<P: Into<(usize, usize)>>(&self, pos: P) -> bool {
 		self.game_info
@@ -840,9 +1331,19 @@ impl Bot {
 		if let Some(townhall) = self.units.my.townhalls.first() {
 			self.start_location = townhall.position();
 		}
-		if let Some(pos) = self.game_info.start_locations.first() {
+		let start_location = self.start_location;
+		self.enemy_start_candidates = self
+			.game_info
+			.start_locations
+			.iter()
+			.copied()
+			.filter(|pos| pos.is_further(1.0, start_location))
+			.collect();
+		// On maps with more than 2 start locations it's only a guess until opponent is scouted
+		if let Some(pos) = self.enemy_start_candidates.first() {
 			self.enemy_start = *pos;
 		}
+		let enemy_start_known = self.enemy_start_candidates.len() == 1;
 
 		let resources = self.units.resources.closer(11.0, self.start_location);
 		self.start_center =
@@ -898,7 +1399,7 @@ impl Bot {
 						Alliance::Own,
 						self.units.my.townhalls.first().map(|t| t.tag()),
 					)
-				} else if center.is_closer(4.0, self.enemy_start_center) {
+				} else if enemy_start_known && center.is_closer(4.0, self.enemy_start_center) {
 					(self.enemy_start, self.enemy_start_center, Alliance::Enemy, None)
 				} else {
 					let location = offsets
@@ -956,15 +1457,12 @@ impl Bot {
 			.collect::<Vec<_>>();
 
 		// Sort expansions by distance to start location
-		let start = Target::Pos(self.start_location);
-		let paths = self
-			.query_pathing(expansions.iter().map(|exp| (start, exp.loc)).collect())
-			.unwrap();
-
-		let paths = expansions
+		let locations = expansions.iter().map(|exp| exp.loc).collect::<Vec<_>>();
+		let paths = locations
 			.iter()
-			.zip(paths)
-			.map(|(exp, path)| (exp.loc, path.unwrap_or(f32::INFINITY)))
+			.copied()
+			.zip(self.ground_distances(self.start_location, &locations))
+			.map(|(loc, path)| (loc, path.unwrap_or(f32::INFINITY)))
 			.collect::<FxHashMap<Point2, f32>>();
 
 		expansions.sort_unstable_by(|a, b| paths[&a.loc].partial_cmp(&paths[&b.loc]).unwrap());
@@ -1027,43 +1525,119 @@ impl Bot {
 		.map(|ps| Ramp::new(ps, &self.game_info.terrain_height, self.start_location))
 		.collect::<Vec<Ramp>>();
 
-		let get_closest_ramp = |loc: Point2| {
-			let (loc_x, loc_y) = <(usize, usize)>::from(loc);
-			let cmp = |r: &&Ramp| {
-				let (x, y) = r.top_center().unwrap();
-				let dx = loc_x.abs_diff(x);
-				let dy = loc_y.abs_diff(y);
-				dx * dx + dy * dy
-			};
-			ramps
-				.iter()
-				.filter(|r| {
-					let upper_len = r.upper().len();
-					upper_len == 2 || upper_len == 5
-				})
-				.min_by_key(cmp)
-				.or_else(|| {
-					ramps
-						.iter()
-						.filter(|r| {
-							let upper_len = r.upper().len();
-							upper_len == 4 || upper_len == 9
-						})
-						.min_by_key(cmp)
-				})
-				.cloned()
-		};
+		self.ramps.all = ramps;
 
-		if let Some(ramp) = get_closest_ramp(self.start_location) {
+		if let Some(ramp) = self.closest_main_ramp(self.start_location) {
 			self.ramps.my = ramp;
 		}
-		if let Some(ramp) = get_closest_ramp(self.enemy_start) {
+		if let Some(ramp) = self.closest_main_ramp(self.enemy_start) {
 			self.ramps.enemy = ramp;
 		}
 
-		self.ramps.all = ramps;
+		self.update_retreat_field();
+	}
+	fn update_retreat_field(&mut self) {
+		// Tiles of own townhall aren't pathable on the grid
+		let townhall = footprint_tiles(self.start_location, 5).collect::<FxHashSet<_>>();
+		let field = distance_field(
+			self.start_location.to_tile(),
+			self.game_info.pathing_grid.dim(),
+			|tile| self.is_pathable(tile) || townhall.contains(&tile),
+		);
+		self.retreat_field = field;
+	}
+	fn closest_main_ramp(&self, loc: Point2) -> Option<Ramp> {
+		let (loc_x, loc_y) = <(usize, usize)>::from(loc);
+		let cmp = |r: &&Ramp| {
+			let (x, y) = r.top_center().unwrap();
+			let dx = loc_x.abs_diff(x);
+			let dy = loc_y.abs_diff(y);
+			dx * dx + dy * dy
+		};
+		let ramps = &self.ramps.all;
+		ramps
+			.iter()
+			.filter(|r| {
+				let upper_len = r.upper().len();
+				upper_len == 2 || upper_len == 5
+			})
+			.min_by_key(cmp)
+			.or_else(|| {
+				ramps
+					.iter()
+					.filter(|r| {
+						let upper_len = r.upper().len();
+						upper_len == 4 || upper_len == 9
+					})
+					.min_by_key(cmp)
+			})
+			.cloned()
+	}
+	fn resolve_enemy_start(&mut self) {
+		const SCOUT_RANGE: f32 = 20.0;
+
+		if self.enemy_start_candidates.len() < 2 {
+			return;
+		}
+
+		let structures = ({
+			#[cfg(not(feature = "enemies_cache"))]
+			{
+				&self.units.enemy.structures
+			}
+			#[cfg(feature = "enemies_cache")]
+			{
+				&self.units.cached.structures
+			}
+		})
+		.iter()
+		.map(|u| u.position())
+		.collect::<Vec<Point2>>();
+
+		let is_scouted = |c: Point2| structures.iter().any(|s| s.is_closer(SCOUT_RANGE, c));
+
+		// Excluding start locations which were seen empty
+		let candidates = self
+			.enemy_start_candidates
+			.iter()
+			.copied()
+			.filter(|c| is_scouted(*c) || !self.is_visible(*c))
+			.collect::<Vec<Point2>>();
+
+		let start = match candidates.iter().copied().find(|c| is_scouted(*c)) {
+			Some(start) => start,
+			None => {
+				if candidates.len() != 1 {
+					if let Some(pos) = candidates.first() {
+						self.enemy_start = *pos;
+						self.enemy_start_candidates = candidates;
+					}
+					return;
+				}
+				candidates[0]
+			}
+		};
+
+		self.enemy_start_candidates = vec![start];
+		self.enemy_start = start;
+
+		let resources = self.units.resources.closer(11.0, start);
+		self.enemy_start_center = (resources.sum(|r| r.position()) + start) / (resources.len() + 1) as f32;
+
+		if let Some(exp) = self
+			.expansions
+			.iter_mut()
+			.find(|exp| exp.center.is_closer(4.0, self.enemy_start_center))
+		{
+			exp.loc = start;
+		}
+		if let Some(ramp) = self.closest_main_ramp(start) {
+			self.ramps.enemy = ramp;
+		}
 	}
 	pub(crate) fn prepare_step(&mut self) {
+		self.pathing_cache.clear();
+
 		let observation = &self.state.observation;
 		self.time = (observation.game_loop() as f32) / FRAMES_PER_SECOND;
 		let common = &observation.common;
@@ -1110,6 +1684,39 @@ impl Bot {
 		}
 		self.current_units = current_units;
 		self.orders = orders;
+
+		// Marking tiles occupied by structures
+		let dim = self.game_info.placement_grid.dim();
+		if self.occupancy_grid.dim() == dim {
+			self.occupancy_grid.fill(Pixel::Empty);
+		} else {
+			self.occupancy_grid = PixelMap::from_elem(dim, Pixel::Empty);
+		}
+		let occupancy_grid = &mut self.occupancy_grid;
+		self.units
+			.my
+			.structures
+			.iter()
+			.chain(self.units.enemy.structures.iter().filter(|s| s.is_visible()))
+			.filter(|s| !s.is_flying())
+			.for_each(|s| {
+				for tile in s.footprint() {
+					if let Some(pixel) = occupancy_grid.get_mut(tile) {
+						*pixel = Pixel::Set;
+					}
+				}
+			});
+
+		// Tracking positions of own units for stuck detection
+		let units = &self.units.my.units;
+		self.position_history.retain(|tag, _| units.contains_tag(*tag));
+		for u in units {
+			let history = self.position_history.entry(u.tag()).or_default();
+			if history.len() == STUCK_HISTORY_LEN {
+				history.pop_front();
+			}
+			history.push_back(u.position());
+		}
 	}
 	pub(crate) fn update_units(&mut self, all_units: Units) {
 		*self.last_units_health.write_lock() = self
@@ -1118,6 +1725,21 @@ impl Bot {
 			.iter()
 			.filter_map(|u| Some((u.tag(), u.hits()?)))
 			.collect();
+		self.previous_units = self
+			.units
+			.all
+			.iter()
+			.map(|u| {
+				(
+					u.tag(),
+					UnitSnapshot {
+						type_id: u.type_id(),
+						position: u.position(),
+						hits: u.hits(),
+					},
+				)
+			})
+			.collect();
 
 		self.units.clear();
 
@@ -1126,7 +1748,7 @@ impl Bot {
 		let mut max_cooldowns = self.max_cooldowns.write_lock();
 		let mut saved_hallucinations = FxHashSet::default();
 		let mut expansions = FxHashMap::default();
-		if self.is_hidden(self.enemy_start) {
+		if self.is_enemy_start_known() && self.is_hidden(self.enemy_start) {
 			expansions.insert(self.enemy_start, (Alliance::Enemy, None));
 		}
 
@@ -1239,6 +1861,7 @@ impl Bot {
 						add_to!(units.units);
 						match u.type_id() {
 							UnitTypeId::SCV | UnitTypeId::Probe | UnitTypeId::Drone => add_to!(units.workers),
+							UnitTypeId::MULE => add_to!(units.mules),
 							UnitTypeId::Larva => add_to!(units.larvas),
 							_ => {}
 						}
@@ -1282,6 +1905,7 @@ impl Bot {
 						add_to!(units.units);
 						match u.type_id() {
 							UnitTypeId::SCV | UnitTypeId::Probe | UnitTypeId::Drone => add_to!(units.workers),
+							UnitTypeId::MULE => add_to!(units.mules),
 							UnitTypeId::Larva => add_to!(units.larvas),
 							_ => {}
 						}
@@ -1342,6 +1966,7 @@ impl Bot {
 				cache.townhalls = enemies.townhalls.clone();
 			}
 			cache.gas_buildings = enemies.gas_buildings.clone();
+			cache.mules = enemies.mules.clone();
 			cache.larvas = enemies.larvas.clone();
 
 			let mut to_remove = Vec::<u64>::new();
@@ -1501,6 +2126,18 @@ impl Bot {
 				}
 			}
 		}
+
+		if self.scouted_enemy_race.is_none() {
+			let enemies = &self.units.enemy;
+			self.scouted_enemy_race = enemies
+				.structures
+				.iter()
+				.chain(enemies.units.iter().filter(|u| !u.is_hallucination()))
+				.map(|u| u.race())
+				.find(|r| !r.is_random());
+		}
+
+		self.resolve_enemy_start();
 	}
 
 	/// Simple wrapper around [`query_placement`](Self::query_placement).
@@ -1513,6 +2150,18 @@ impl Bot {
 		.unwrap()[0] == ActionResult::Success
 	}
 	/// Simple wrapper around [`query_placement`](Self::query_placement).
+	/// Checks if given lifted terran building can land on given position.
+	///
+	/// Returns `false` if building is not lifted.
+	pub fn can_land(&self, building: &Unit, pos: Point2) -> bool {
+		let ability = match LAND_ABILITIES.get(&building.type_id()) {
+			Some(ability) => *ability,
+			None => return false,
+		};
+		self.query_placement(vec![(ability, pos, Some(building.tag()))], false)
+			.map_or(false, |res| res[0] == ActionResult::Success)
+	}
+	/// Simple wrapper around [`query_placement`](Self::query_placement).
 	/// Multi-version of [`can_place`](Self::can_place).
 	pub fn can_place_some(&self, places: Vec<(UnitTypeId, Point2)>) -> Vec<bool> {
 		self.query_placement(
@@ -1559,6 +2208,13 @@ impl Bot {
 					return Some(near);
 				}
 
+				let size = self
+					.game_data
+					.abilities
+					.get(&ability)
+					.and_then(|data| data.footprint_radius)
+					.map(|radius| (radius * 2.0) as usize);
+
 				let placement_step = options.step;
 				for distance in (placement_step..options.max_distance).step_by(placement_step as usize) {
 					let positions = (-distance..=distance)
@@ -1571,7 +2227,12 @@ impl Bot {
 								near.offset(distance as f32, offset as f32),
 							]
 						})
+						.filter(|pos| size.map_or(true, |size| self.is_footprint_free(*pos, size)))
 						.collect::<Vec<Point2>>();
+					if positions.is_empty() {
+						continue;
+					}
+
 					let results = self
 						.query_placement(positions.iter().map(|pos| (ability, *pos, None)).collect(), false)
 						.unwrap();
@@ -1663,6 +2324,151 @@ impl Bot {
 			.min_by(|(_, path1), (_, path2)| path1.partial_cmp(path2).unwrap())
 			.map(|(exp, _)| exp)
 	}
+	/// Returns townhall placement of own natural expansion
+	/// (i.e. expansion closest to start location by ground path, excluding main base).
+	pub fn natural(&self) -> Option<Point2> {
+		self.expansions_by_path(self.start_location)
+			.into_iter()
+			.find(|(exp, _)| exp.loc.is_further(1.0, self.start_location))
+			.map(|(exp, _)| exp.loc)
+	}
+	/// Returns townhall placement of opponent's natural expansion
+	/// (i.e. expansion closest to [`enemy_start`](Self::enemy_start) by ground path, excluding main base).
+	pub fn enemy_natural(&self) -> Option<Point2> {
+		self.expansions_by_path(self.enemy_start)
+			.into_iter()
+			.find(|(exp, _)| exp.loc.is_further(1.0, self.enemy_start))
+			.map(|(exp, _)| exp.loc)
+	}
+	/// Returns sensible default position to gather army for defense.
+	///
+	/// If natural expansion is taken, it's a point in front of natural townhall towards opponent,
+	/// otherwise it's a point on high ground behind the main ramp.
+	pub fn rally_point(&self) -> Point2 {
+		if let Some(natural) = self
+			.natural()
+			.filter(|natural| self.owned_expansions().any(|exp| exp.loc == *natural))
+		{
+			return natural.towards(self.enemy_start, 6.0);
+		}
+		self.ramps.my.top_center().map_or(self.start_location, |top| {
+			Point2::from(top).towards(self.start_location, 2.0)
+		})
+	}
+	/// Returns mineral fields of expansion closest to given point
+	/// sorted by distance to expansion's townhall placement in ascending order.
+	pub fn mineral_patches_near(&self, base: Point2) -> Units {
+		self.expansions
+			.iter()
+			.min_by(|a, b| {
+				a.loc
+					.distance_squared(base)
+					.partial_cmp(&b.loc.distance_squared(base))
+					.unwrap()
+			})
+			.map_or_else(Units::new, |exp| {
+				self.units.mineral_fields.find_tags(&exp.minerals)
+			})
+	}
+	/// Splits own workers across mineral fields of main base, 2 workers per field at most.
+	///
+	/// Closest fields are filled first, each field is assigned to closest free worker.
+	/// Intended to be called once at the start of the game.
+	pub fn split_workers_on_start(&self) {
+		let patches = self.mineral_patches_near(self.start_location);
+		let mut workers = self.units.my.workers.clone();
+
+		for _ in 0..2 {
+			for patch in &patches {
+				let worker = match workers.closest(patch) {
+					Some(worker) => worker.tag(),
+					None => return,
+				};
+				if let Some(worker) = workers.remove(worker) {
+					worker.gather(patch.tag(), false);
+				}
+			}
+		}
+	}
+	/// Returns (townhall placement, resources center) of all [`expansions`](Self::expansions)
+	/// sorted by ground path distance from given point.
+	///
+	/// Unreachable expansions are filtered out, note that location of occupied expansion
+	/// is usually unreachable, because it's blocked by townhall.
+	/// If pathing query fails, expansions are sorted by straight distance.
+	pub fn expansions_sorted_by(&self, from: Point2) -> Vec<(Point2, Point2)> {
+		self.expansions_by_path(from)
+			.into_iter()
+			.map(|(exp, _)| (exp.loc, exp.center))
+			.collect()
+	}
+	/// Returns expansion closest to given point by ground path distance
+	/// or `None` if there aren't any reachable expansions.
+	pub fn closest_expansion_to(&self, from: Point2) -> Option<&Expansion> {
+		self.expansions_by_path(from).first().map(|(exp, _)| *exp)
+	}
+	/// Returns ground path distances from given point to every location
+	/// or `None` for unreachable ones.
+	///
+	/// Falls back to straight distances if pathing can't be queried from API.
+	fn ground_distances(&self, from: Point2, locations: &[Point2]) -> Vec<Option<f32>> {
+		if self.api.is_some() {
+			let start = Target::Pos(from);
+			match self.query_pathing(locations.iter().map(|loc| (start, *loc)).collect()) {
+				Ok(paths) => return paths,
+				Err(e) => warn!("Pathing query failed, using straight distances: {}", e),
+			}
+		}
+		locations.iter().map(|loc| Some(from.distance(*loc))).collect()
+	}
+	fn expansions_by_path(&self, from: Point2) -> Vec<(&Expansion, f32)> {
+		let locations = self.expansions.iter().map(|exp| exp.loc).collect::<Vec<_>>();
+		let paths = self.ground_distances(from, &locations);
+
+		let mut expansions = self
+			.expansions
+			.iter()
+			.zip(paths)
+			.filter_map(|(exp, path)| Some((exp, path?)))
+			.collect::<Vec<_>>();
+		expansions.sort_unstable_by(|(_, path1), (_, path2)| path1.partial_cmp(path2).unwrap());
+		expansions
+	}
+	/// Checks if actual race of opponent is known
+	/// (i.e. opponent isn't random or it's race was already detected).
+	pub fn is_enemy_race_confirmed(&self) -> bool {
+		!self.enemy_race.is_random()
+	}
+	/// Returns actual race of opponent, if any of enemy units or structures was seen.
+	///
+	/// Unlike [`enemy_race`](Self::enemy_race) doesn't depend on requested race and
+	/// returns `None` until something is scouted.
+	pub fn scouted_enemy_race(&self) -> Option<Race> {
+		self.scouted_enemy_race
+	}
+	/// Returns tags of enemy units, which were seen as hallucinations.
+	///
+	/// Hallucination flag of enemy units is populated only while they're visible,
+	/// this set keeps them remembered when they become snapshots.
+	pub fn known_hallucinations(&self) -> &FxHashSet<u64> {
+		&self.saved_hallucinations
+	}
+	/// Checks if enemy unit with given tag was seen as hallucination.
+	pub fn is_known_hallucination(&self, tag: u64) -> bool {
+		self.saved_hallucinations.contains(&tag)
+	}
+	/// Returns all start locations, where opponent can be.
+	///
+	/// On maps with more than 2 start locations [`enemy_start`](Self::enemy_start)
+	/// is only a guess, until opponent is scouted.
+	pub fn possible_enemy_starts(&self) -> &[Point2] {
+		&self.enemy_start_candidates
+	}
+	/// Checks if opponent's start location is known
+	/// (i.e. map has only 2 start locations or opponent was already scouted).
+	pub fn is_enemy_start_known(&self) -> bool {
+		self.enemy_start_candidates.len() == 1
+	}
 	/// Returns all [`expansions`](Self::expansions) taken by bot.
 	pub fn owned_expansions(&self) -> impl Iterator<Item = &Expansion> {
 		self.expansions.iter().filter(|exp| exp.alliance.is_mine())
@@ -1675,9 +2481,443 @@ impl Bot {
 	pub fn free_expansions(&self) -> impl Iterator<Item = &Expansion> {
 		self.expansions.iter().filter(|exp| exp.alliance.is_neutral())
 	}
+	/// Stutter-step micro: orders unit to attack threat if weapon is ready and threat is in range,
+	/// otherwise orders unit to step back from threat on [`distance_per_step`](Unit::distance_per_step).
+	///
+	/// Returns `true` if attack was ordered.
+	pub fn kite(&self, unit: &Unit, threat: &Unit) -> bool {
+		if !unit.on_cooldown() && unit.in_real_range(threat, 0.0) {
+			unit.attack(Target::Tag(threat.tag()), false);
+			true
+		} else {
+			let pos = unit.position();
+			unit.move_to(
+				Target::Pos(pos.towards(threat.position(), -unit.distance_per_step())),
+				false,
+			);
+			false
+		}
+	}
+	/// Orders unit to attack target in range with the lowest [`effective_hp_vs`](Unit::effective_hp_vs) it,
+	/// or to attack-move to the closest target it can attack if none are in range.
+	///
+	/// Returns `true` if target in range was found.
+	pub fn focus_fire(&self, unit: &Unit, targets: &Units) -> bool {
+		if let Some(target) = targets
+			.iter()
+			.filter(|t| unit.in_real_range(t, 0.0))
+			.map(|t| (t, t.effective_hp_vs(unit)))
+			.min_by(|(_, hp1), (_, hp2)| hp1.partial_cmp(hp2).unwrap())
+			.map(|(t, _)| t)
+		{
+			unit.attack(Target::Tag(target.tag()), false);
+			return true;
+		}
+
+		if let Some(closest) = targets.iter().filter(|t| unit.can_attack_unit(t)).closest(unit) {
+			unit.attack(Target::Pos(closest.position()), false);
+		}
+		false
+	}
+	/// Returns closest known enemy, which given unit can attack.
+	/// Flying enemies are skipped if `air_ok` is `false`.
+	///
+	/// Enemies are taken from `units.cached` when `enemies_cache` feature is enabled.
+	pub fn closest_enemy(&self, unit: &Unit, air_ok: bool) -> Option<&Unit> {
+		({
+			#[cfg(not(feature = "enemies_cache"))]
+			{
+				&self.units.enemy.all
+			}
+			#[cfg(feature = "enemies_cache")]
+			{
+				&self.units.cached.all
+			}
+		})
+		.iter()
+		.filter(|e| (air_ok || !e.is_flying()) && unit.can_attack_unit(e))
+		.closest(unit)
+	}
+	/// Checks if cloaked or burrowed unit with given radius would be revealed on given position
+	/// by known enemy detectors or scanner sweeps.
+	///
+	/// Enemy detectors are taken from `units.cached` when `enemies_cache` feature is enabled.
+	pub fn enemy_detection_near(&self, pos: Point2, radius: f32) -> bool {
+		({
+			#[cfg(not(feature = "enemies_cache"))]
+			{
+				&self.units.enemy.all
+			}
+			#[cfg(feature = "enemies_cache")]
+			{
+				&self.units.cached.all
+			}
+		})
+		.iter()
+		.filter(|u| u.is_detector())
+		.any(|d| pos.is_closer(radius + d.radius() + d.detect_range(), d))
+			|| self
+				.state
+				.observation
+				.raw
+				.effects
+				.iter()
+				.filter(|e| e.id == EffectId::ScannerSweep && e.alliance.is_enemy())
+				.any(|e| e.positions.iter().any(|p| pos.is_closer(radius + e.radius, *p)))
+	}
+	/// Returns effects on the map, which deal damage to units in their area
+	/// (see [`Effect::is_dangerous`]). Effects of both players are included.
+	pub fn dangerous_effects(&self) -> impl Iterator<Item = &Effect> {
+		self.state
+			.observation
+			.raw
+			.effects
+			.iter()
+			.filter(|e| e.is_dangerous())
+	}
+	/// Returns target positions of corrosive biles, which haven't landed yet
+	/// (parsed from [`RavagerCorrosiveBileCP`](EffectId::RavagerCorrosiveBileCP) effects).
+	///
+	/// Bile lands in 2.5 seconds after cast, time left can be checked in
+	/// [`remaining_duration`](Effect::remaining_duration) of effect.
+	pub fn incoming_bile_targets(&self) -> Vec<Point2> {
+		self.effect_positions(EffectId::RavagerCorrosiveBileCP)
+	}
+	/// Returns target positions of tactical nukes
+	/// (parsed from [`NukePersistent`](EffectId::NukePersistent) effects).
+	///
+	/// [`Alert::NuclearLaunchDetected`](crate::game_state::Alert::NuclearLaunchDetected)
+	/// also notifies about launch, but doesn't contain target position.
+	pub fn nuke_targets(&self) -> Vec<Point2> {
+		self.effect_positions(EffectId::NukePersistent)
+	}
+	fn effect_positions(&self, id: EffectId) -> Vec<Point2> {
+		self.state
+			.observation
+			.raw
+			.effects
+			.iter()
+			.filter(|e| e.id == id)
+			.flat_map(|e| e.positions.iter().copied())
+			.collect()
+	}
+	/// Returns all passengers of own transports and bunkers paired with tags of their carriers.
+	pub fn loaded_units(&self) -> Vec<(u64, &PassengerUnit)> {
+		self.units
+			.my
+			.all
+			.iter()
+			.flat_map(|u| u.passengers().iter().map(move |p| (u.tag(), p)))
+			.collect()
+	}
+	/// Returns total space taken in all own transports and bunkers.
+	pub fn total_cargo_used(&self) -> u32 {
+		self.units
+			.my
+			.all
+			.iter()
+			.filter_map(|u| u.cargo_space_taken())
+			.sum()
+	}
+	/// Returns total number of workers assigned to own gas buildings.
+	pub fn gas_workers(&self) -> u32 {
+		self.units
+			.my
+			.gas_buildings
+			.iter()
+			.filter_map(|g| g.assigned_harvesters())
+			.sum()
+	}
+	/// Returns total number of workers assigned to mineral fields of own townhalls.
+	pub fn mineral_workers(&self) -> u32 {
+		self.units
+			.my
+			.townhalls
+			.iter()
+			.filter_map(|t| t.assigned_harvesters())
+			.sum()
+	}
+	/// Returns ratio of assigned to ideal workers of all own gas buildings
+	/// (`1.0` means fully saturated, `0.0` if there are no gas buildings).
+	pub fn gas_saturation(&self) -> f32 {
+		let (assigned, ideal) = self
+			.units
+			.my
+			.gas_buildings
+			.iter()
+			.filter_map(|g| Some((g.assigned_harvesters()?, g.ideal_harvesters()?)))
+			.fold((0, 0), |(assigned, ideal), (a, i)| (assigned + a, ideal + i));
+		if ideal == 0 {
+			0.0
+		} else {
+			assigned as f32 / ideal as f32
+		}
+	}
+	/// Returns type, position and hits of all units seen on previous step mapped by their tags.
+	///
+	/// Only these values are stored to avoid cloning whole units each step.
+	/// Combined with [`game_loop_delta`](Self::game_loop_delta) it can be used to calculate velocity of units.
+	pub fn previous_units(&self) -> &FxHashMap<u64, UnitSnapshot> {
+		&self.previous_units
+	}
+	/// Returns snapshot of unit with given tag from previous step.
+	pub fn previous_unit(&self, tag: u64) -> Option<&UnitSnapshot> {
+		self.previous_units.get(&tag)
+	}
+	/// Returns estimated velocity (in tiles per game second) of visible unit with given tag,
+	/// calculated from its position on current and previous steps.
+	///
+	/// Returns `None` if unit wasn't seen on both steps.
+	pub fn velocity_of(&self, tag: u64) -> Option<Point2> {
+		let prev = self.previous_units.get(&tag)?;
+		let unit = self.units.all.get(tag)?;
+		if self.game_loop_delta == 0 {
+			return None;
+		}
+		let seconds = self.game_loop_delta as f32 / FRAMES_PER_SECOND;
+		Some((unit.position() - prev.position) / seconds)
+	}
+	/// Returns distance from unit to target of its current order or `None` if unit has no target.
+	///
+	/// Target units are resolved with [`get_unit`](Self::get_unit).
+	pub fn distance_to_order_target(&self, unit: &Unit) -> Option<f32> {
+		let target = match unit.target() {
+			Target::Pos(pos) => pos,
+			Target::Tag(tag) => self.get_unit(tag)?.position(),
+			Target::None => return None,
+		};
+		Some(unit.distance(target))
+	}
+	/// Checks if own unit has an order, but hasn't moved during last few steps
+	/// while its order target is still far away.
+	///
+	/// Units attacking targets in range are not considered stuck.
+	pub fn is_unit_stuck(&self, unit: &Unit) -> bool {
+		let history = match self.position_history.get(&unit.tag()) {
+			Some(history) if history.len() == STUCK_HISTORY_LEN => history,
+			_ => return false,
+		};
+		match self.distance_to_order_target(unit) {
+			Some(distance) if distance > unit.radius() + 2.0 => {}
+			_ => return false,
+		}
+		if let Some(target) = unit.target_tag().and_then(|tag| self.get_unit(tag)) {
+			if unit.in_real_range(target, 1.0) {
+				return false;
+			}
+		}
+
+		let pos = unit.position();
+		history.iter().all(|p| p.is_closer(STUCK_DISTANCE, pos))
+	}
+	/// Orders unit to move to random pathable position nearby to dislodge it.
+	/// Can be used with units detected by [`is_unit_stuck`](Self::is_unit_stuck).
+	///
+	/// Returns `false` if there're no pathable positions around.
+	pub fn unstick(&self, unit: &Unit) -> bool {
+		const UNSTICK_RADIUS: isize = 3;
+
+		let pos = unit.position();
+		let candidates = iproduct!(-UNSTICK_RADIUS..=UNSTICK_RADIUS, -UNSTICK_RADIUS..=UNSTICK_RADIUS)
+			.filter(|(x, y)| (*x, *y) != (0, 0))
+			.map(|(x, y)| pos.offset(x as f32, y as f32))
+			.filter(|p| p.is_closer(UNSTICK_RADIUS as f32, pos) && self.is_pathable(*p))
+			.collect::<Vec<Point2>>();
+
+		match candidates.choose(&mut thread_rng()) {
+			Some(target) => {
+				unit.move_to(Target::Pos(*target), false);
+				true
+			}
+			None => false,
+		}
+	}
+	/// Checks if unit with given tag belongs to bot (includes units that are currently not visible,
+	/// e.g. loaded in transports).
+	pub fn owns(&self, tag: u64) -> bool {
+		self.owned_tags.contains(&tag)
+	}
+	/// Returns unit with given tag from [`units.all`](AllUnits::all)
+	/// or `None` if there's no such unit on current step.
+	pub fn unit_by_tag(&self, tag: u64) -> Option<&Unit> {
+		self.units.all.get(tag)
+	}
+	/// Returns unit with given tag, searching in [`units.all`](AllUnits::all) first,
+	/// then in enemy units cache (for enemies hidden in fog of war) when `enemies_cache` feature is enabled.
+	pub fn get_unit(&self, tag: u64) -> Option<&Unit> {
+		#[cfg(not(feature = "enemies_cache"))]
+		{
+			self.units.all.get(tag)
+		}
+		#[cfg(feature = "enemies_cache")]
+		{
+			self.units.all.get(tag).or_else(|| self.units.cached.all.get(tag))
+		}
+	}
+	/// Returns units with given tags found by [`get_unit`](Self::get_unit).
+	/// Tags of units which weren't found are skipped.
+	pub fn get_units<'a, T: IntoIterator<Item = &'a u64>>(&self, tags: T) -> Units {
+		tags.into_iter()
+			.filter_map(|tag| self.get_unit(*tag).cloned())
+			.collect()
+	}
+	/// Assigns given label (e.g. name of a task or squad) to own unit with given tag.
+	/// Unit can have multiple labels.
+	///
+	/// Labels of dead units are removed automatically.
+	pub fn assign(&mut self, label: &str, tag: u64) {
+		self.unit_labels.entry(label.to_string()).or_default().insert(tag);
+	}
+	/// Removes given label from unit with given tag.
+	pub fn unassign(&mut self, label: &str, tag: u64) {
+		if let Some(tags) = self.unit_labels.get_mut(label) {
+			tags.remove(&tag);
+		}
+	}
+	/// Returns tags of units with given label.
+	pub fn tags_with_label(&self, label: &str) -> Option<&FxHashSet<u64>> {
+		self.unit_labels.get(label)
+	}
+	/// Returns own units with given label.
+	pub fn units_with_label(&self, label: &str) -> Units {
+		self.unit_labels
+			.get(label)
+			.map_or_else(Units::new, |tags| self.units.my.all.find_tags(tags))
+	}
+	/// Orders idle queens with enough energy to inject larva into closest ready hatcheries,
+	/// which aren't injected yet.
+	///
+	/// Returns number of issued injects.
+	pub fn auto_inject(&self) -> usize {
+		let my = &self.units.my;
+		let mut queens = my
+			.units
+			.iter()
+			.filter(|q| q.is_idle() && q.can_inject())
+			.collect::<Vec<&Unit>>();
+		let injecting = my
+			.units
+			.iter()
+			.filter(|q| q.type_id() == UnitTypeId::Queen && q.is_using(AbilityId::EffectInjectLarva))
+			.filter_map(|q| q.target_tag())
+			.collect::<FxHashSet<u64>>();
+
+		let mut count = 0;
+		for hatch in my.townhalls.iter().filter(|h| {
+			matches!(
+				h.type_id(),
+				UnitTypeId::Hatchery | UnitTypeId::Lair | UnitTypeId::Hive
+			) && h.is_ready()
+				&& !h.has_buff(BuffId::QueenSpawnLarvaTimer)
+				&& !injecting.contains(&h.tag())
+		}) {
+			if queens.is_empty() {
+				break;
+			}
+			if let Some((i, queen)) = queens.iter().enumerate().min_by(|(_, q1), (_, q2)| {
+				q1.distance_squared(hatch)
+					.partial_cmp(&q2.distance_squared(hatch))
+					.unwrap()
+			}) {
+				queen.inject(hatch.tag(), false);
+				queens.swap_remove(i);
+				count += 1;
+			}
+		}
+		count
+	}
+	/// Returns all units standing on given ramp.
+	pub fn units_on_ramp(&self, ramp: &Ramp) -> Units {
+		self.units.all.filter(|u| ramp.contains_point(u.position()))
+	}
+	/// Simulates fight between given armies to predict its outcome.
+	///
+	/// Simulation is simplified: units are considered to be in range of each other
+	/// and focus targets, which die the fastest. Dps is taken from [`real_weapon_vs`](Unit::real_weapon_vs).
+	pub fn simulate_combat(&self, my: &Units, enemy: &Units) -> CombatResult {
+		combat::simulate(my, enemy)
+	}
+	/// Returns tiles of terran wall on given ramp, which are not covered by ready own structures.
+	///
+	/// Wall consists of [`corner_depots`](Ramp::corner_depots)
+	/// and [`barracks_correct_placement`](Ramp::barracks_correct_placement).
+	/// Returns empty `Vec` when wall is complete.
+	pub fn ramp_wall_gaps(&self, ramp: &Ramp) -> Vec<Point2> {
+		let mut wall = Vec::new();
+		if let Some(depots) = ramp.corner_depots() {
+			for depot in depots {
+				wall.extend(footprint_tiles(depot, 2));
+			}
+		}
+		if let Some(barracks) = ramp.barracks_correct_placement() {
+			wall.extend(footprint_tiles(barracks, 3));
+		}
+
+		let covered = self
+			.units
+			.my
+			.structures
+			.iter()
+			.filter(|s| s.is_ready() && !s.is_flying())
+			.flat_map(|s| s.footprint())
+			.collect::<FxHashSet<_>>();
+
+		wall.into_iter()
+			.filter(|tile| !covered.contains(tile))
+			.map(Point2::from)
+			.collect()
+	}
+	/// Checks if terran wall on given ramp has no [`gaps`](Self::ramp_wall_gaps).
+	pub fn is_wall_complete(&self, ramp: &Ramp) -> bool {
+		self.ramp_wall_gaps(ramp).is_empty()
+	}
+	/// Returns enemy units, which can attack given unit from their current positions.
+	///
+	/// Real ranges of enemies are used, `gap` extends them. Takes into account if enemies
+	/// can attack air or ground units. Returns empty collection if unit is cloaked and not detected.
+	pub fn threats_to(&self, unit: &Unit, gap: f32) -> Units {
+		if unit.is_invisible() {
+			return Units::new();
+		}
+
+		({
+			#[cfg(not(feature = "enemies_cache"))]
+			{
+				&self.units.enemy.all
+			}
+			#[cfg(feature = "enemies_cache")]
+			{
+				&self.units.cached.all
+			}
+		})
+		.filter(|e| e.can_attack_unit(unit) && e.in_real_range(unit, gap))
+	}
+	/// Sends raw request to API and extracts needed data from the response with given function.
+	///
+	/// Returns error if request failed or response contains errors.
+	///
+	/// # Examples
+	/// ```
+	/// let mut req = Request::new();
+	/// req.mut_query().mut_pathing().push(pathing);
+	/// let distance = self.query(req, |res| res.get_query().get_pathing()[0].distance)?;
+	/// ```
+	pub fn query<T, F>(&self, req: Request, extract: F) -> SC2Result<T>
+	where
+		F: FnOnce(&Response) -> T,
+	{
+		let res = self.api().send(req)?;
+		let errors = res.get_error();
+		if !errors.is_empty() {
+			return Err(SC2Error::Response(errors.join("; ")).into());
+		}
+		Ok(extract(&res))
+	}
 	/// Sends pathing requests to API.
 	///
 	/// Takes `Vec` of (start, goal), where `start` is position or unit tag and `goal` is position.
+	/// All paths are sent in a single request, so it's faster to query many paths at once,
+	/// than calling this method for each of them (see also [`queue_pathing`](Self::queue_pathing)).
 	///
 	/// Returns `Vec` ordered by input values,
 	/// where element is distance of path from start to goal or `None` if there's no path.
@@ -1704,6 +2944,127 @@ impl Bot {
 			.map(|result| result.distance)
 			.collect())
 	}
+	/// Finds ground path between given positions locally (without requests to API)
+	/// using A* on [`pathing_grid`](GameInfo::pathing_grid).
+	///
+	/// Returns centers of path tiles including start and goal or `None` if there's no path.
+	/// Note that pathing grid is taken on game start and doesn't include structures built later.
+	pub fn find_path(&self, start: Point2, goal: Point2) -> Option<Vec<Point2>> {
+		astar(start.to_tile(), goal.to_tile(), |tile| self.is_pathable(tile))
+			.map(|path| path.into_iter().map(|(x, y)| Point2::from_tile(x, y)).collect())
+	}
+	/// Returns position 5 tiles away from given point along ground path to start location.
+	///
+	/// Paths are taken from distances to start location, which are computed once on game start
+	/// using [`pathing_grid`](GameInfo::pathing_grid), so it's cheap to call for many units,
+	/// but structures built later aren't considered.
+	///
+	/// Falls back to start location if there's no path or start location is closer.
+	pub fn retreat_position(&self, from: Point2) -> Point2 {
+		const RETREAT_DISTANCE: f32 = 5.0;
+
+		let cost = |tile: (usize, usize)| self.retreat_field.get(tile).copied().unwrap_or(u32::MAX);
+
+		let mut current = from.to_tile();
+		if cost(current) == u32::MAX {
+			return self.start_location;
+		}
+		let mut traveled = 0.0;
+		while traveled < RETREAT_DISTANCE {
+			let (x, y) = current;
+			// Moving to the neighbor closest to start, corners of obstacles can't be cut
+			let next = iproduct!(x.saturating_sub(1)..=x + 1, y.saturating_sub(1)..=y + 1)
+				.filter(|&(nx, ny)| cost((nx, y)) != u32::MAX && cost((x, ny)) != u32::MAX)
+				.min_by_key(|tile| cost(*tile))
+				.filter(|next| cost(*next) < cost(current));
+			match next {
+				Some(next) => {
+					traveled += Point2::from_tile(x, y).distance(Point2::from_tile(next.0, next.1));
+					current = next;
+				}
+				None => return self.start_location,
+			}
+		}
+		Point2::from_tile(current.0, current.1)
+	}
+	/// Returns distance of ground path between given positions or `None` if there's no path.
+	///
+	/// Results are cached by grid tiles of positions until the end of current step,
+	/// so repeated queries don't make additional requests to API.
+	pub fn cached_pathing_distance(&self, start: Point2, goal: Point2) -> Option<f32> {
+		self.pathing_cache
+			.get_or_create(&(start.into(), goal.into()), || {
+				self.query_pathing(vec![(Target::Pos(start), goal)])
+					.ok()
+					.and_then(|res| res.first().copied().flatten())
+			})
+	}
+	/// Sends request for available abilities of given units to API.
+	///
+	/// Unlike abilities cached each step, can ignore resource requirements
+	/// when `ignore_resource_requirements` is `true`.
+	///
+	/// Returns map of unit tags to their available abilities.
+	pub fn query_abilities(
+		&self,
+		tags: &[u64],
+		ignore_resource_requirements: bool,
+	) -> SC2Result<FxHashMap<u64, Vec<AbilityId>>> {
+		let mut req = Request::new();
+		let req_query = req.mut_query();
+		req_query.set_ignore_resource_requirements(ignore_resource_requirements);
+		let req_abilities = req_query.mut_abilities();
+
+		for &tag in tags {
+			let mut req_unit = RequestQueryAvailableAbilities::new();
+			req_unit.set_unit_tag(tag);
+			req_abilities.push(req_unit);
+		}
+
+		let res = self.api().send(req)?;
+		Ok(res
+			.get_query()
+			.get_abilities()
+			.iter()
+			.map(|a| {
+				(
+					a.get_unit_tag(),
+					a.get_abilities()
+						.iter()
+						.filter_map(|ab| AbilityId::from_i32(ab.get_ability_id()))
+						.collect(),
+				)
+			})
+			.collect())
+	}
+	/// Adds pathing query to the batch, which will be sent with
+	/// [`flush_pathing_queue`](Self::flush_pathing_queue).
+	///
+	/// Returns index of query result in the batch.
+	pub fn queue_pathing(&mut self, start: Target, goal: Point2) -> usize {
+		self.pathing_queue.push((start, goal));
+		self.pathing_queue.len() - 1
+	}
+	/// Sends all queued with [`queue_pathing`](Self::queue_pathing) paths in a single request to API.
+	///
+	/// Returns `Vec` ordered by indices of queries.
+	/// Distances between positions are also saved in cache used by
+	/// [`cached_pathing_distance`](Self::cached_pathing_distance).
+	pub fn flush_pathing_queue(&mut self) -> SC2Result<Vec<Option<f32>>> {
+		if self.pathing_queue.is_empty() {
+			return Ok(Vec::new());
+		}
+
+		let paths = std::mem::take(&mut self.pathing_queue);
+		let results = self.query_pathing(paths.clone())?;
+		for ((start, goal), distance) in paths.into_iter().zip(&results) {
+			if let Target::Pos(start) = start {
+				self.pathing_cache
+					.get_or_create(&(start.into(), goal.into()), || *distance);
+			}
+		}
+		Ok(results)
+	}
 	/// Sends placement requests to API.
 	/// Takes creep, psionic matrix, and other stuff into account.
 	///
@@ -1747,15 +3108,78 @@ impl Bot {
 
 	/// Leaves current game, which is counted as Defeat for bot.
 	///
-	/// Note: [`on_end`] will not be called, if needed use [`debug.end_game`] instead.
+	/// [`on_end`] will be called with [`Defeat`](GameResult::Defeat) before leaving.
 	///
 	/// [`on_end`]: crate::Player::on_end
-	/// [`debug.end_game`]: Debugger::end_game
 	pub fn leave(&mut self) -> SC2Result<()> {
 		self.game_left = true;
 		Ok(())
 	}
 
+	/// Clears all state collected during the game, so bot can be used to play next one.
+	///
+	/// Keeps connection to the client, [`game_step`](Self::game_step), [`action_limit`](Self::action_limit),
+	/// [`opponent_id`](Self::opponent_id), fog settings and event log.
+	/// Called automatically by runners before each game.
+	pub fn reset(&mut self) {
+		self.game_left = false;
+		self.race = Race::Random;
+		self.enemy_race = Race::Random;
+		self.player_id = Default::default();
+		self.enemy_player_id = Default::default();
+		self.actions.clear();
+		self.commander = Default::default();
+		self.dropped_actions.clear();
+		self.debug = Default::default();
+		self.game_info = Default::default();
+		self.game_data = Default::default();
+		self.state = Default::default();
+		self.race_values = Default::default();
+		self.data_for_unit = Default::default();
+		self.units = Default::default();
+		self.abilities_units = Default::default();
+		self.orders.clear();
+		self.current_units.clear();
+		self.enemies_ordered.clear();
+		self.enemies_current.clear();
+		self.saved_hallucinations.clear();
+		self.time = Default::default();
+		self.minerals = Default::default();
+		self.vespene = Default::default();
+		self.supply_army = Default::default();
+		self.supply_workers = Default::default();
+		self.supply_cap = Default::default();
+		self.supply_used = Default::default();
+		self.supply_left = Default::default();
+		self.start_location = Default::default();
+		self.enemy_start = Default::default();
+		self.start_center = Default::default();
+		self.enemy_start_center = Default::default();
+		self.enemy_start_candidates.clear();
+		self.scouted_enemy_race = None;
+		self.techlab_tags = Default::default();
+		self.reactor_tags = Default::default();
+		self.expansions.clear();
+		self.max_cooldowns = Default::default();
+		self.last_units_health = Default::default();
+		self.previous_units.clear();
+		self.position_history.clear();
+		self.vision_blockers.clear();
+		self.ramps = Default::default();
+		self.enemy_upgrades = Default::default();
+		self.owned_tags.clear();
+		self.under_construction.clear();
+		self.available_frames = Default::default();
+		self.last_step_duration = Default::default();
+		self.game_loop_delta = Default::default();
+		self.game_result = None;
+		self.pathing_cache.clear();
+		self.pathing_queue.clear();
+		self.occupancy_grid = Default::default();
+		self.retreat_field = Default::default();
+		self.unit_labels.clear();
+	}
+
 	pub(crate) fn close_client(&mut self) {
 		if let Some(api) = &self.api {
 			let mut req = Request::new();
@@ -1794,6 +3218,8 @@ impl Default for Bot {
 			opponent_id: Default::default(),
 			actions: Default::default(),
 			commander: Default::default(),
+			action_limit: None,
+			dropped_actions: Default::default(),
 			debug: Default::default(),
 			game_info: Default::default(),
 			game_data: Default::default(),
@@ -1816,11 +3242,15 @@ impl Default for Bot {
 			enemy_start: Default::default(),
 			start_center: Default::default(),
 			enemy_start_center: Default::default(),
+			enemy_start_candidates: Default::default(),
+			scouted_enemy_race: None,
 			techlab_tags: Default::default(),
 			reactor_tags: Default::default(),
 			expansions: Default::default(),
 			max_cooldowns: Default::default(),
 			last_units_health: Default::default(),
+			previous_units: Default::default(),
+			position_history: Default::default(),
 			vision_blockers: Default::default(),
 			ramps: Default::default(),
 			enemy_upgrades: Default::default(),
@@ -1830,6 +3260,16 @@ impl Default for Bot {
 			enemies_current: Default::default(),
 			saved_hallucinations: Default::default(),
 			available_frames: Default::default(),
+			last_step_duration: Default::default(),
+			game_loop_delta: Default::default(),
+			game_result: None,
+			pathing_cache: Default::default(),
+			pathing_queue: Default::default(),
+			occupancy_grid: Default::default(),
+			retreat_field: Default::default(),
+			unit_labels: Default::default(),
+			#[cfg(feature = "serde")]
+			event_log: None,
 		}
 	}
 }
@@ -1839,3 +3279,253 @@ impl Drop for Bot {
 		self.close_client();
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		pixel_map::Visibility,
+		test_utils::{game_data, proto_unit, shared_data, unit, unit_type, MAP_SIZE},
+	};
+	use sc2_proto::raw::Alliance as ProtoAlliance;
+
+	const SPAWNS: [(f32, f32); 4] = [(10.5, 10.5), (53.5, 10.5), (10.5, 53.5), (53.5, 53.5)];
+
+	fn four_spawn_bot() -> Bot {
+		let mut bot = Bot::default();
+		bot.game_info.start_locations = SPAWNS.iter().map(|pos| Point2::from(*pos)).collect();
+		bot.start_location = Point2::from(SPAWNS[0]);
+		bot.enemy_start_candidates = bot.game_info.start_locations[1..].to_vec();
+		bot.enemy_start = bot.enemy_start_candidates[0];
+		bot.state.observation.raw.visibility = Array2::from_elem((MAP_SIZE, MAP_SIZE), Visibility::Hidden);
+		bot
+	}
+
+	fn reveal(bot: &mut Bot, pos: (f32, f32), radius: f32) {
+		let pos = Point2::from(pos);
+		for tile in iproduct!(0..MAP_SIZE, 0..MAP_SIZE) {
+			if pos.is_closer(radius, tile) {
+				bot.state.observation.raw.visibility[tile] = Visibility::Visible;
+			}
+		}
+	}
+
+	#[test]
+	fn resolve_enemy_start_drops_candidates_seen_empty() {
+		let mut bot = four_spawn_bot();
+
+		reveal(&mut bot, SPAWNS[1], 10.0);
+		bot.resolve_enemy_start();
+		assert_eq!(
+			bot.enemy_start_candidates,
+			vec![Point2::from(SPAWNS[2]), Point2::from(SPAWNS[3])]
+		);
+		assert_eq!(bot.enemy_start, Point2::from(SPAWNS[2]));
+
+		// Opponent must be at the last location left
+		reveal(&mut bot, SPAWNS[2], 10.0);
+		bot.resolve_enemy_start();
+		assert_eq!(bot.enemy_start_candidates, vec![Point2::from(SPAWNS[3])]);
+		assert_eq!(bot.enemy_start, Point2::from(SPAWNS[3]));
+	}
+
+	#[test]
+	fn resolve_enemy_start_by_enemy_structure() {
+		let mut bot = four_spawn_bot();
+		let data = shared_data(game_data(
+			vec![unit_type(UnitTypeId::Pylon, Race::Protoss)],
+			vec![],
+		));
+		let mut pylon = proto_unit(1, UnitTypeId::Pylon, Point2::new(45.0, 47.0));
+		pylon.set_alliance(ProtoAlliance::Enemy);
+		let pylon = unit(&data, &pylon);
+		#[cfg(not(feature = "enemies_cache"))]
+		bot.units.enemy.structures.push(pylon);
+		#[cfg(feature = "enemies_cache")]
+		bot.units.cached.structures.push(pylon);
+
+		bot.resolve_enemy_start();
+		assert_eq!(bot.enemy_start_candidates, vec![Point2::from(SPAWNS[3])]);
+		assert_eq!(bot.enemy_start, Point2::from(SPAWNS[3]));
+	}
+
+	/// Sets up bot as on the first step of the game with given spawns
+	/// and mineral lines (3 vertical mineral fields centered on given points).
+	fn start_game(bot: &mut Bot, start: (f32, f32), enemy_start: (f32, f32), mineral_lines: &[(f32, f32)]) {
+		let mut mineral = unit_type(UnitTypeId::MineralField, Race::Random);
+		mineral.has_minerals = true;
+		let data = shared_data(game_data(
+			vec![unit_type(UnitTypeId::CommandCenter, Race::Terran), mineral],
+			vec![],
+		));
+
+		bot.game_info.start_locations = vec![Point2::from(start), Point2::from(enemy_start)];
+		bot.game_info.placement_grid = Array2::from_elem((MAP_SIZE, MAP_SIZE), Pixel::Empty);
+		let townhall = proto_unit(1, UnitTypeId::CommandCenter, Point2::from(start));
+		bot.units.my.townhalls.push(unit(&data, &townhall));
+		let mut tag = 2;
+		for (x, y) in mineral_lines {
+			for dy in [-2.0, 0.0, 2.0] {
+				let mut mineral = proto_unit(tag, UnitTypeId::MineralField, Point2::new(*x, y + dy));
+				mineral.set_alliance(ProtoAlliance::Neutral);
+				bot.units.resources.push(unit(&data, &mineral));
+				tag += 1;
+			}
+		}
+
+		bot.prepare_start();
+	}
+
+	#[test]
+	fn next_game_on_other_map_recomputes_locations() {
+		let mut bot = Bot::default();
+
+		start_game(
+			&mut bot,
+			(10.5, 10.5),
+			(53.5, 53.5),
+			&[(4.0, 10.5), (60.0, 53.5), (32.0, 32.5)],
+		);
+		assert_eq!(bot.start_location, Point2::new(10.5, 10.5));
+		assert_eq!(bot.enemy_start, Point2::new(53.5, 53.5));
+		assert_eq!(bot.expansions.len(), 3);
+		assert_eq!(bot.expansions[0].loc, Point2::new(10.5, 10.5));
+
+		bot.reset();
+		assert!(bot.expansions.is_empty());
+		assert!(bot.enemy_start_candidates.is_empty());
+		assert!(bot.units.resources.is_empty());
+
+		start_game(&mut bot, (50.5, 12.5), (12.5, 50.5), &[(57.0, 12.5), (6.0, 50.5)]);
+		assert_eq!(bot.start_location, Point2::new(50.5, 12.5));
+		assert_eq!(bot.enemy_start, Point2::new(12.5, 50.5));
+		assert_eq!(bot.enemy_start_candidates, vec![Point2::new(12.5, 50.5)]);
+		let locations = bot.expansions.iter().map(|exp| exp.loc).collect::<Vec<_>>();
+		assert_eq!(locations, vec![Point2::new(50.5, 12.5), Point2::new(12.5, 50.5)]);
+		assert_eq!(bot.expansions[0].alliance, Alliance::Own);
+		assert_eq!(bot.expansions[1].alliance, Alliance::Enemy);
+	}
+
+	#[test]
+	fn retreat_position_follows_path_to_start() {
+		let mut bot = Bot::default();
+		let mut grid = Array2::from_elem((MAP_SIZE, MAP_SIZE), Pixel::Empty);
+		// Wall between start location and enemy, with gap at the top
+		for y in 0..40 {
+			grid[(30, y)] = Pixel::Set;
+		}
+		bot.game_info.pathing_grid = grid;
+		bot.start_location = Point2::new(10.5, 10.5);
+		bot.update_retreat_field();
+
+		assert_eq!(
+			bot.retreat_position(Point2::new(20.5, 10.5)),
+			Point2::new(15.5, 10.5)
+		);
+
+		let around_wall = bot.retreat_position(Point2::new(35.5, 10.5));
+		assert!(around_wall.x > 30.0 && around_wall.y > 10.5);
+
+		assert_eq!(bot.retreat_position(Point2::new(12.5, 10.5)), bot.start_location);
+		assert_eq!(bot.retreat_position(Point2::new(30.5, 5.5)), bot.start_location);
+	}
+
+	#[test]
+	fn train_from_any_picks_different_producers() {
+		let mut bot = Bot::default();
+		let mut marine = unit_type(UnitTypeId::Marine, Race::Terran);
+		marine.ability = Some(AbilityId::BarracksTrainMarine);
+		marine.mineral_cost = 50;
+		marine.food_required = 1.0;
+		let data = Rs::new(DataForUnit {
+			commander: Rs::clone(&bot.commander),
+			game_data: Rs::new(game_data(
+				vec![unit_type(UnitTypeId::Barracks, Race::Terran), marine],
+				vec![],
+			)),
+			..Default::default()
+		});
+		bot.game_data = Rs::clone(&data.game_data);
+		for tag in 1..=2 {
+			let pos = Point2::new(20.5, tag as f32 * 5.0);
+			let barracks = unit(&data, &proto_unit(tag, UnitTypeId::Barracks, pos));
+			bot.units.my.structures.push(barracks.clone());
+			bot.units.my.all.push(barracks);
+		}
+		bot.minerals = 200;
+		bot.supply_left = 10;
+
+		assert!(bot.train_from_any(UnitTypeId::Marine, None, false));
+		assert!(bot.train_from_any(UnitTypeId::Marine, None, false));
+		// Both barracks already have orders on this step
+		assert!(!bot.train_from_any(UnitTypeId::Marine, None, false));
+		assert_eq!(bot.minerals, 100);
+
+		let commander = bot.commander.read_lock();
+		let mut tags = commander.commands[&(AbilityId::BarracksTrainMarine, Target::None, false)].clone();
+		tags.sort_unstable();
+		assert_eq!(tags, vec![1, 2]);
+	}
+
+	#[cfg(feature = "rayon")]
+	fn marines(count: u64) -> (SharedUnitData, Units) {
+		let data = shared_data(game_data(
+			vec![unit_type(UnitTypeId::Marine, Race::Terran)],
+			vec![],
+		));
+		let units = (0..count)
+			.map(|tag| {
+				let pos = Point2::new((tag % 50) as f32 + 5.5, (tag / 50 % 50) as f32 + 5.5);
+				unit(&data, &proto_unit(tag, UnitTypeId::Marine, pos))
+			})
+			.collect();
+		(data, units)
+	}
+
+	#[cfg(feature = "rayon")]
+	#[test]
+	fn par_for_each_unit_commands_every_unit() {
+		let (data, units) = marines(500);
+		let bot = Bot::default();
+
+		bot.par_for_each_unit(&units, |u| u.hold_position(false));
+
+		let commander = data.commander.read_lock();
+		let mut tags = commander.commands[&(AbilityId::HoldPosition, Target::None, false)].clone();
+		tags.sort_unstable();
+		assert_eq!(tags, (0..500).collect::<Vec<u64>>());
+	}
+
+	#[cfg(feature = "rayon")]
+	#[test]
+	#[ignore = "benchmark, run with `cargo test --release -- --ignored --nocapture`"]
+	fn par_for_each_unit_benchmark() {
+		use std::time::Instant;
+
+		let (data, units) = marines(2500);
+		let bot = Bot::default();
+		let micro = |u: &Unit| {
+			if let Some(center) = units.closer(5.0, u).center() {
+				u.move_to(Target::Pos(center), false);
+			}
+		};
+		let commanded = || {
+			let mut commander = data.commander.write_lock();
+			let count = commander.commands.values().map(Vec::len).sum::<usize>();
+			commander.commands.clear();
+			count
+		};
+
+		let now = Instant::now();
+		units.iter().for_each(&micro);
+		let sequential = now.elapsed();
+		assert_eq!(commanded(), units.len());
+
+		let now = Instant::now();
+		bot.par_for_each_unit(&units, &micro);
+		let parallel = now.elapsed();
+		assert_eq!(commanded(), units.len());
+
+		println!("sequential: {:?}, parallel: {:?}", sequential, parallel);
+	}
+}
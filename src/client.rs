@@ -6,30 +6,117 @@
 use crate::{
 	api::API,
 	bot::{Bot, LockOwned, Rs},
+	consts::FRAMES_PER_SECOND,
 	game_state::update_state,
 	paths::*,
-	player::Computer,
-	IntoProto, IntoSC2, Player, PlayerSettings,
+	player::{Computer, GameResult},
+	IntoProto, IntoSC2, Player, PlayerSettings, SpatialSetup,
+};
+use sc2_proto::sc2api::{
+	PlayerSetup, PlayerType, PortSet, Request, RequestCreateGame, Response, SpatialCameraSetup, Status,
 };
-use sc2_proto::sc2api::{PlayerSetup, PlayerType, PortSet, Request, RequestCreateGame, Status};
 use std::{
 	error::Error,
 	fmt,
 	fs::File,
-	io::Write,
+	io::{self, ErrorKind, Write},
 	net::{TcpListener, TcpStream},
 	ops::{Deref, DerefMut},
 	process::{Child, Command},
+	thread,
+	time::{Duration, Instant},
 };
-use tungstenite::{connect, stream::MaybeTlsStream, WebSocket};
+use tungstenite::{connect, error::ProtocolError, stream::MaybeTlsStream, WebSocket};
 
 pub(crate) type WS = WebSocket<MaybeTlsStream<TcpStream>>;
+/// Result returned from bot callbacks and game runners.
+///
+/// Errors produced by SC2 client and [`API`] are [`SC2Error`],
+/// which can be matched after `downcast_ref::<SC2Error>()`.
 pub type SC2Result<T> = Result<T, Box<dyn Error>>;
 
+/// Error returned from the SC2 API and game runners.
+#[derive(Debug)]
+pub enum SC2Error {
+	/// Error in websocket connection with SC2 client.
+	WebSocket(tungstenite::Error),
+	/// Failed to serialize request or deserialize response.
+	Proto(protobuf::ProtobufError),
+	/// SC2 client refused to create or join the game.
+	GameCreate(String),
+	/// Input-output error.
+	Io(io::Error),
+	/// Connection with SC2 client was lost.
+	Disconnected,
+	/// SC2 client didn't respond in time set by [`API::set_timeout`].
+	Timeout,
+	/// SC2 client returned errors in response.
+	Response(String),
+}
+impl SC2Error {
+	fn game_create<E: fmt::Debug>(error: E, details: &str) -> Self {
+		Self::GameCreate(format!("{:?}: {}", error, details))
+	}
+}
+impl fmt::Display for SC2Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::WebSocket(e) => write!(f, "WebSocket error: {}", e),
+			Self::Proto(e) => write!(f, "Protobuf error: {}", e),
+			Self::GameCreate(e) => write!(f, "Game creation error: {}", e),
+			Self::Io(e) => write!(f, "IO error: {}", e),
+			Self::Disconnected => write!(f, "Disconnected from SC2 client"),
+			Self::Timeout => write!(f, "SC2 client response timed out"),
+			Self::Response(e) => write!(f, "SC2 response error: {}", e),
+		}
+	}
+}
+impl Error for SC2Error {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Self::WebSocket(e) => Some(e),
+			Self::Proto(e) => Some(e),
+			Self::Io(e) => Some(e),
+			Self::GameCreate(_) | Self::Disconnected | Self::Timeout | Self::Response(_) => None,
+		}
+	}
+}
+impl From<tungstenite::Error> for SC2Error {
+	fn from(e: tungstenite::Error) -> Self {
+		match e {
+			tungstenite::Error::ConnectionClosed
+			| tungstenite::Error::AlreadyClosed
+			| tungstenite::Error::Protocol(ProtocolError::ResetWithoutClosingHandshake) => Self::Disconnected,
+			tungstenite::Error::Io(e) => e.into(),
+			e => Self::WebSocket(e),
+		}
+	}
+}
+impl From<protobuf::ProtobufError> for SC2Error {
+	fn from(e: protobuf::ProtobufError) -> Self {
+		Self::Proto(e)
+	}
+}
+impl From<io::Error> for SC2Error {
+	fn from(e: io::Error) -> Self {
+		match e.kind() {
+			ErrorKind::ConnectionReset
+			| ErrorKind::ConnectionAborted
+			| ErrorKind::BrokenPipe
+			| ErrorKind::UnexpectedEof => Self::Disconnected,
+			ErrorKind::WouldBlock | ErrorKind::TimedOut => Self::Timeout,
+			_ => Self::Io(e),
+		}
+	}
+}
+
 #[cfg(all(feature = "wine_sc2", not(target_os = "linux")))]
 compile_error!("Wine is only supported on linux");
 
 const HOST: &str = "127.0.0.1";
+/// Number of attempts to reconnect to SC2 client when connection was lost during the game.
+const RECONNECT_ATTEMPTS: usize = 5;
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
 const SC2_BINARY: &str = {
 	#[cfg(any(target_os = "windows", feature = "wine_sc2"))]
 	{
@@ -120,12 +207,13 @@ where
 		debug!("Launching SC2 process");
 		self.bot.process = Some(launch_client(&self.sc2_path, port, self.sc2_version));
 		debug!("Connecting to websocket");
-		self.bot.api = Some(API::new(connect_to_websocket(HOST, port)?));
+		self.bot.api = Some(API::new(connect_to_websocket(HOST, port)?, HOST, port));
 		Ok(())
 	}
 
 	/// Runs requested game.
 	pub fn run_game(&mut self) -> SC2Result<()> {
+		self.bot.reset();
 		let settings = self.bot.get_player_settings();
 		let api = self.bot.api();
 
@@ -144,13 +232,9 @@ where
 		let res = api.send(req)?;
 		let res_create_game = res.get_create_game();
 		if res_create_game.has_error() {
-			let err = format!(
-				"{:?}: {}",
-				res_create_game.get_error(),
-				res_create_game.get_error_details()
-			);
+			let err = SC2Error::game_create(res_create_game.get_error(), res_create_game.get_error_details());
 			error!("{}", err);
-			panic!("{}", err);
+			return Err(err.into());
 		}
 
 		debug!("Sending JoinGame request");
@@ -160,11 +244,7 @@ where
 		set_static_data(self.bot)?;
 
 		debug!("Entered main loop");
-		play_first_step(self.bot, self.realtime)?;
-		let mut iteration = 0;
-		while play_step(self.bot, iteration, self.realtime)? {
-			iteration += 1;
-		}
+		play_game(self.bot, self.realtime)?;
 		debug!("Game finished");
 
 		if let Some(path) = &self.save_replay_as {
@@ -173,6 +253,37 @@ where
 		Ok(())
 	}
 
+	/// Runs `games_each` games vs every given computer opponent one by one.
+	///
+	/// Client must be launched before, it's relaunched between games, so each game starts from clean state.
+	/// Returns results of games in order, failed game doesn't stop the series and is recorded as error.
+	/// Series is stopped early only if client can't be relaunched, then fewer results are returned.
+	pub fn run_games(&mut self, opponents: &[Computer], games_each: usize) -> Vec<SC2Result<GameResult>> {
+		let mut results = Vec::with_capacity(opponents.len() * games_each);
+		for computer in opponents {
+			self.computer = *computer;
+			for _ in 0..games_each {
+				if !results.is_empty() {
+					self.close();
+					if let Err(e) = self.launch() {
+						error!("Can't relaunch SC2 client: {}", e);
+						results.push(Err(e));
+						return results;
+					}
+				}
+
+				let result = self
+					.run_game()
+					.map(|_| self.bot.game_result.take().unwrap_or(GameResult::Undecided));
+				if let Err(e) = &result {
+					error!("Game vs {:?} failed: {}", computer, e);
+				}
+				results.push(result);
+			}
+		}
+		results
+	}
+
 	/// Changes map to play on.
 	///
 	/// # Panics
@@ -244,15 +355,20 @@ where
 		self.bot.process = Some(launch_client(&self.sc2_path, port_bot, self.sc2_version));
 
 		debug!("Connecting to host websocket");
-		self.human.api = Some(API::new(connect_to_websocket(HOST, port_human)?));
+		self.human.api = Some(API::new(
+			connect_to_websocket(HOST, port_human)?,
+			HOST,
+			port_human,
+		));
 		debug!("Connecting to client websocket");
-		self.bot.api = Some(API::new(connect_to_websocket(HOST, port_bot)?));
+		self.bot.api = Some(API::new(connect_to_websocket(HOST, port_bot)?, HOST, port_bot));
 
 		Ok(())
 	}
 
 	/// Runs requested game.
 	pub fn run_game(&mut self) -> SC2Result<()> {
+		self.bot.reset();
 		let bot_settings = self.bot.get_player_settings();
 		let human_api = self.human.api.as_ref().unwrap();
 
@@ -270,13 +386,9 @@ where
 		let res = human_api.send(req)?;
 		let res_create_game = res.get_create_game();
 		if res_create_game.has_error() {
-			let err = format!(
-				"{:?}: {}",
-				res_create_game.get_error(),
-				res_create_game.get_error_details()
-			);
+			let err = SC2Error::game_create(res_create_game.get_error(), res_create_game.get_error_details());
 			error!("{}", err);
-			panic!("{}", err);
+			return Err(err.into());
 		}
 
 		debug!("Sending JoinGame request to both processes");
@@ -299,11 +411,7 @@ where
 		set_static_data(self.bot)?;
 
 		debug!("Entered main loop");
-		play_first_step(self.bot, self.realtime)?;
-		let mut iteration = 0;
-		while play_step(self.bot, iteration, self.realtime)? {
-			iteration += 1;
-		}
+		play_game(self.bot, self.realtime)?;
 		debug!("Game finished");
 
 		if let Some(path) = &self.save_replay_as {
@@ -360,27 +468,13 @@ impl Drop for Human {
 	}
 }
 
-#[derive(Debug)]
-struct ProtoError(String);
-impl ProtoError {
-	fn new<E: fmt::Debug>(error: E, details: &str) -> Self {
-		Self(format!("{:?}: {}", error, details))
-	}
-}
-impl fmt::Display for ProtoError {
-	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		write!(f, "{}", self.0)
-	}
-}
-impl Error for ProtoError {}
-
 struct Ports {
 	// shared: i32,
 	server: (i32, i32),
 	client: Vec<(i32, i32)>,
 }
 
-/// Additional launch options for [`run_vs_computer`] and [`run_vs_human`].
+/// Additional launch options for [`run_vs_computer`], [`run_vs_human`] and [`run_vs_bot`].
 #[derive(Default)]
 pub struct LaunchOptions<'a> {
 	/// SC2 version to play on, otherwise latest available will be used.
@@ -425,7 +519,7 @@ where
 	debug!("Starting ladder game");
 
 	debug!("Connecting to websocket");
-	bot.api = Some(API::new(connect_to_websocket(host, port)?));
+	bot.api = Some(API::new(connect_to_websocket(host, port)?, host, port));
 
 	debug!("Sending JoinGame request");
 
@@ -447,12 +541,7 @@ where
 	set_static_data(bot)?;
 
 	debug!("Entered main loop");
-	// Main loop
-	let mut iteration = 0;
-	play_first_step(bot, false)?;
-	while play_step(bot, iteration, false)? {
-		iteration += 1;
-	}
+	play_game(bot, false)?;
 	debug!("Game finished");
 
 	Ok(())
@@ -476,6 +565,125 @@ where
 	Ok(())
 }
 
+/// Simple function to run game between two bots.
+///
+/// Each bot plays in it's own SC2 client, first bot's client hosts the game.
+/// Replay is saved from the first bot's perspective.
+pub fn run_vs_bot<A, B>(bot_a: &mut A, bot_b: &mut B, map_name: &str, options: LaunchOptions) -> SC2Result<()>
+where
+	A: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
+	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
+{
+	debug!("Starting bot vs bot");
+	bot_a.reset();
+	bot_b.reset();
+	let sc2_path = get_path_to_sc2();
+	let map_path = get_map_path(&sc2_path, map_name);
+
+	let ports = get_unused_ports(2);
+	let (port_a, port_b) = (ports[0], ports[1]);
+
+	debug!("Launching host SC2 process");
+	bot_a.process = Some(launch_client(&sc2_path, port_a, options.sc2_version));
+	debug!("Launching client SC2 process");
+	bot_b.process = Some(launch_client(&sc2_path, port_b, options.sc2_version));
+
+	debug!("Connecting to host websocket");
+	bot_a.api = Some(API::new(connect_to_websocket(HOST, port_a)?, HOST, port_a));
+	debug!("Connecting to client websocket");
+	bot_b.api = Some(API::new(connect_to_websocket(HOST, port_b)?, HOST, port_b));
+
+	let settings_a = bot_a.get_player_settings();
+	let settings_b = bot_b.get_player_settings();
+
+	debug!("Sending CreateGame request to host process");
+	let mut req = Request::new();
+	let req_create_game = req.mut_create_game();
+
+	req_create_game.mut_local_map().set_map_path(map_path);
+	create_player_setup(&settings_a, req_create_game);
+	create_player_setup(&settings_b, req_create_game);
+	req_create_game.set_realtime(options.realtime);
+
+	let res = bot_a.api().send(req)?;
+	let res_create_game = res.get_create_game();
+	if res_create_game.has_error() {
+		let err = SC2Error::game_create(res_create_game.get_error(), res_create_game.get_error_details());
+		error!("{}", err);
+		return Err(err.into());
+	}
+
+	debug!("Sending JoinGame request to both processes");
+	let ports = get_unused_ports(6);
+	let ports = Ports {
+		server: (ports[0], ports[1]),
+		client: vec![(ports[2], ports[3]), (ports[4], ports[5])],
+	};
+	join_game2(&settings_a, bot_a.api(), Some(&ports))?;
+	join_game2(&settings_b, bot_b.api(), Some(&ports))?;
+	bot_a.player_id = wait_join(bot_a.api())?;
+	bot_b.player_id = wait_join(bot_b.api())?;
+
+	set_static_data(bot_a)?;
+	set_static_data(bot_b)?;
+
+	debug!("Entered main loop");
+	let res = play_game_vs_bot(bot_a, bot_b, options.realtime);
+	// Game can't continue for any of the bots when one of the clients is lost
+	let res = end_game_on_lost_connection(bot_a, res);
+	end_game_on_lost_connection(bot_b, res)?;
+	debug!("Game finished");
+
+	if let Some(path) = options.save_replay_as {
+		save_replay(bot_a.api(), path)?;
+	}
+	Ok(())
+}
+
+fn play_game_vs_bot<A, B>(bot_a: &mut A, bot_b: &mut B, realtime: bool) -> SC2Result<()>
+where
+	A: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
+	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
+{
+	// Steps are requested from both clients before waiting for responses,
+	// because in multiplayer game each client waits for the other one to step.
+	start_first_step(bot_a, realtime)?;
+	start_first_step(bot_b, realtime)?;
+	if !realtime {
+		bot_a.api().send_only(step_request(bot_a))?;
+		bot_b.api().send_only(step_request(bot_b))?;
+		bot_a.api().wait_response()?;
+		bot_b.api().wait_response()?;
+	}
+
+	let mut iteration = 0;
+	let (mut running_a, mut running_b) = (true, true);
+	while running_a || running_b {
+		if running_a {
+			running_a = start_step(bot_a, iteration, realtime)?;
+		}
+		if running_b {
+			running_b = start_step(bot_b, iteration, realtime)?;
+		}
+		if !realtime {
+			if running_a {
+				bot_a.api().send_only(step_request(bot_a))?;
+			}
+			if running_b {
+				bot_b.api().send_only(step_request(bot_b))?;
+			}
+			if running_a {
+				bot_a.api().wait_response()?;
+			}
+			if running_b {
+				bot_b.api().wait_response()?;
+			}
+		}
+		iteration += 1;
+	}
+	Ok(())
+}
+
 // Portpicker
 fn get_unused_port() -> i32 {
 	(5000..65535)
@@ -558,9 +766,13 @@ fn join_game2(settings: &PlayerSettings, api: &API, ports: Option<&Ports>) -> SC
 
 	let options = req_join_game.mut_options();
 	options.set_raw(true);
-	options.set_score(true);
-	// options.mut_feature_layer()
-	// options.mut_render();
+	options.set_score(settings.score);
+	if let Some(setup) = &settings.feature_layer {
+		set_spatial_setup(options.mut_feature_layer(), setup);
+	}
+	if let Some(setup) = &settings.render {
+		set_spatial_setup(options.mut_render(), setup);
+	}
 	options.set_show_cloaked(true);
 	options.set_show_burrowed_shadows(true);
 	options.set_show_placeholders(true);
@@ -589,32 +801,156 @@ fn join_game2(settings: &PlayerSettings, api: &API, ports: Option<&Ports>) -> SC
 	api.send_only(req)?;
 	Ok(())
 }
+fn set_spatial_setup(proto: &mut SpatialCameraSetup, setup: &SpatialSetup) {
+	let resolution = proto.mut_resolution();
+	resolution.set_x(setup.resolution.x as i32);
+	resolution.set_y(setup.resolution.y as i32);
+	let minimap_resolution = proto.mut_minimap_resolution();
+	minimap_resolution.set_x(setup.minimap_resolution.x as i32);
+	minimap_resolution.set_y(setup.minimap_resolution.y as i32);
+	proto.set_width(setup.width);
+	proto.set_crop_to_playable_area(setup.crop_to_playable_area);
+	proto.set_allow_cheating_layers(setup.allow_cheating_layers);
+}
 fn wait_join(api: &API) -> SC2Result<u32> {
 	let res = api.wait_response()?;
 
 	let res_join_game = res.get_join_game();
 	if res_join_game.has_error() {
-		let err = ProtoError::new(res_join_game.get_error(), res_join_game.get_error_details());
+		let err = SC2Error::game_create(res_join_game.get_error(), res_join_game.get_error_details());
 		error!("{}", err);
-		Err(Box::new(err))
+		Err(err.into())
 	} else {
 		Ok(res_join_game.get_player_id())
 	}
 }
 
+/// Reconnects to SC2 client after connection was lost.
+///
+/// In realtime games only one attempt is made without delays, to not stall the step loop.
+fn reconnect<B>(bot: &mut B, realtime: bool) -> SC2Result<()>
+where
+	B: DerefMut<Target = Bot> + Deref<Target = Bot>,
+{
+	let attempts = if realtime { 1 } else { RECONNECT_ATTEMPTS };
+	let api = bot.api.as_mut().expect("API is not initialized");
+	let (host, port) = api.address();
+	let host = host.to_string();
+	for attempt in 1..=attempts {
+		warn!(
+			"Lost connection to SC2 client, reconnecting (attempt {} of {})",
+			attempt, attempts
+		);
+		match api.reconnect(&host, port) {
+			Ok(()) => return Ok(()),
+			Err(e) => warn!("Failed to reconnect: {}", e),
+		}
+		if attempt < attempts {
+			thread::sleep(RECONNECT_DELAY);
+		}
+	}
+	Err(SC2Error::Disconnected.into())
+}
+/// Sends request which doesn't change game state (e.g. observation),
+/// repeating it once after reconnect when connection was lost.
+fn send_reconnecting<B>(bot: &mut B, req: Request, realtime: bool) -> SC2Result<Response>
+where
+	B: DerefMut<Target = Bot> + Deref<Target = Bot>,
+{
+	match bot.api().send(req.clone()) {
+		Err(SC2Error::Disconnected) => {
+			reconnect(bot, realtime)?;
+			bot.api().send(req).map_err(Into::into)
+		}
+		res => res.map_err(Into::into),
+	}
+}
+/// Sends request which changes game state (e.g. actions or step).
+///
+/// When connection was lost, request is not repeated, because it may be already applied.
+/// Bot only reconnects and the game state is resynced with the next observation.
+fn send_or_resync<B>(bot: &mut B, req: Request, realtime: bool) -> SC2Result<()>
+where
+	B: DerefMut<Target = Bot> + Deref<Target = Bot>,
+{
+	match bot.api().send_request(req) {
+		Err(SC2Error::Disconnected) => {
+			warn!("Lost connection to SC2 client during request, it may be not applied");
+			reconnect(bot, realtime)
+		}
+		res => res.map_err(Into::into),
+	}
+}
+
+/// Plays the game from the first step until it's over.
+fn play_game<B>(bot: &mut B, realtime: bool) -> SC2Result<()>
+where
+	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
+{
+	let mut play = || -> SC2Result<()> {
+		play_first_step(bot, realtime)?;
+		let mut iteration = 0;
+		while play_step(bot, iteration, realtime)? {
+			iteration += 1;
+		}
+		Ok(())
+	};
+	let res = play();
+	end_game_on_lost_connection(bot, res)
+}
+/// Ends the game for bot when it failed because connection with SC2 client was lost
+/// or client stopped responding, so [`on_end`](Player::on_end) is called with
+/// [`Undecided`](GameResult::Undecided) result and event log is flushed.
+///
+/// Given result is returned unchanged, errors of `on_end` and flushing are only logged.
+fn end_game_on_lost_connection<B, T>(bot: &mut B, res: SC2Result<T>) -> SC2Result<T>
+where
+	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
+{
+	let lost = res
+		.as_ref()
+		.err()
+		.and_then(|e| e.downcast_ref::<SC2Error>())
+		.map_or(false, |e| matches!(e, SC2Error::Disconnected | SC2Error::Timeout));
+	if lost && bot.game_result.is_none() {
+		warn!("Lost connection to SC2 client, ending the game");
+		bot.game_result = Some(GameResult::Undecided);
+		if let Err(e) = bot.on_end(GameResult::Undecided) {
+			error!("Error in on_end: {}", e);
+		}
+		#[cfg(feature = "serde")]
+		if let Err(e) = bot.flush_event_log() {
+			error!("Can't flush event log: {}", e);
+		}
+	}
+	res
+}
 fn play_first_step<B>(bot: &mut B, realtime: bool) -> SC2Result<()>
+where
+	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
+{
+	start_first_step(bot, realtime)?;
+	if !realtime {
+		let req = step_request(bot);
+		send_or_resync(bot, req, realtime)?;
+	}
+	Ok(())
+}
+fn start_first_step<B>(bot: &mut B, realtime: bool) -> SC2Result<()>
 where
 	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
 {
 	let mut req = Request::new();
 	req.mut_observation().set_disable_fog(true);
-	let res = bot.api().send(req)?;
+	let res = send_reconnecting(bot, req, realtime)?;
 
 	bot.init_data_for_unit();
 	let events = update_state(bot, res.get_observation())?;
 	bot.prepare_start();
 	bot.prepare_step();
 
+	#[cfg(feature = "serde")]
+	bot.log_events(&events)?;
 	for e in events {
 		bot.on_event(e)?;
 	}
@@ -628,41 +964,75 @@ where
 			actions.push(a.into_proto());
 		}
 		bot.clear_actions();
-		bot.api().send_request(req)?;
-	}
-	if !realtime {
-		let mut req = Request::new();
-		req.mut_step().set_count(bot.game_step.get_locked());
-		bot.api().send_request(req)?;
+		send_or_resync(bot, req, realtime)?;
 	}
 	Ok(())
 }
 
 fn play_step<B>(bot: &mut B, iteration: usize, realtime: bool) -> SC2Result<bool>
+where
+	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
+{
+	if !start_step(bot, iteration, realtime)? {
+		return Ok(false);
+	}
+	if !realtime {
+		let req = step_request(bot);
+		send_or_resync(bot, req, realtime)?;
+	}
+	Ok(true)
+}
+/// Runs one step of the bot without requesting game to advance.
+/// Returns `false` when the game is over.
+fn start_step<B>(bot: &mut B, iteration: usize, realtime: bool) -> SC2Result<bool>
 where
 	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
 {
 	let mut req = Request::new();
 	req.mut_observation().set_disable_fog(bot.disable_fog);
-	let res = bot.api().send(req)?;
+	let res = send_reconnecting(bot, req, realtime)?;
 
 	if matches!(res.get_status(), Status::ended) {
 		let result = res.get_observation().get_player_result()[bot.player_id as usize - 1]
 			.get_result()
 			.into_sc2();
 		debug!("Result for bot: {:?}", result);
+		bot.game_result = Some(result);
 		bot.on_end(result)?;
+		#[cfg(feature = "serde")]
+		bot.flush_event_log()?;
 		return Ok(false);
 	}
 
+	let last_game_loop = bot.state.observation.game_loop();
 	let events = update_state(bot, res.get_observation())?;
+	bot.game_loop_delta = bot.state.observation.game_loop().saturating_sub(last_game_loop);
 	bot.prepare_step();
 
+	#[cfg(feature = "serde")]
+	bot.log_events(&events)?;
 	for e in events {
 		bot.on_event(e)?;
 	}
+	let step_start = Instant::now();
 	bot.on_step(iteration)?;
+	bot.last_step_duration = step_start.elapsed();
+	if realtime {
+		let budget = Duration::from_secs_f32(bot.game_step() as f32 / FRAMES_PER_SECOND);
+		if bot.last_step_duration > budget {
+			warn!(
+				"Step {} took {:?}, which exceeds game step budget of {:?}",
+				iteration, bot.last_step_duration, budget
+			);
+		}
+	}
 	if bot.game_left {
+		debug!("Result for bot: {:?}", GameResult::Defeat);
+		bot.game_result = Some(GameResult::Defeat);
+		bot.on_end(GameResult::Defeat)?;
+		#[cfg(feature = "serde")]
+		bot.flush_event_log()?;
+
 		let mut req = Request::new();
 		req.mut_leave_game();
 		bot.api().send_request(req)?;
@@ -678,7 +1048,7 @@ where
 			actions.push(a.into_proto());
 		}
 		bot.clear_actions();
-		bot.api().send_request(req)?;
+		send_or_resync(bot, req, realtime)?;
 		/*
 		let res = api.send(req);
 		let results = res.get_action().get_result();
@@ -696,15 +1066,15 @@ where
 			debug_commands.push(cmd.into_proto())
 		}
 		bot.clear_debug_commands();
-		bot.api().send_request(req)?;
-	}
-	if !realtime {
-		let mut req = Request::new();
-		req.mut_step().set_count(bot.game_step.get_locked());
-		bot.api().send_request(req)?;
+		send_or_resync(bot, req, realtime)?;
 	}
 	Ok(true)
 }
+fn step_request(bot: &Bot) -> Request {
+	let mut req = Request::new();
+	req.mut_step().set_count(bot.game_step.get_locked());
+	req
+}
 
 fn save_replay(api: &API, path: &str) -> SC2Result<()> {
 	let mut req = Request::new();
@@ -777,3 +1147,68 @@ fn connect_to_websocket(host: &str, port: i32) -> SC2Result<WS> {
 	};
 	Ok(ws)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::player::Race;
+	use std::cell::RefCell;
+
+	#[derive(Default)]
+	struct EndRecorder {
+		bot: Bot,
+		ends: RefCell<Vec<GameResult>>,
+	}
+	impl Deref for EndRecorder {
+		type Target = Bot;
+
+		fn deref(&self) -> &Self::Target {
+			&self.bot
+		}
+	}
+	impl DerefMut for EndRecorder {
+		fn deref_mut(&mut self) -> &mut Self::Target {
+			&mut self.bot
+		}
+	}
+	impl Player for EndRecorder {
+		fn get_player_settings(&self) -> PlayerSettings {
+			PlayerSettings::new(Race::Random)
+		}
+		fn on_end(&self, result: GameResult) -> SC2Result<()> {
+			self.ends.borrow_mut().push(result);
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn lost_connection_ends_game() {
+		let mut bot = EndRecorder::default();
+
+		let res = end_game_on_lost_connection(&mut bot, SC2Result::<()>::Err(SC2Error::Disconnected.into()));
+		assert!(matches!(
+			res.unwrap_err().downcast_ref::<SC2Error>(),
+			Some(SC2Error::Disconnected)
+		));
+		assert_eq!(bot.game_result, Some(GameResult::Undecided));
+		assert_eq!(*bot.ends.borrow(), vec![GameResult::Undecided]);
+
+		// Game is ended only once
+		let _ = end_game_on_lost_connection(&mut bot, SC2Result::<()>::Err(SC2Error::Timeout.into()));
+		assert_eq!(bot.ends.borrow().len(), 1);
+	}
+
+	#[test]
+	fn other_errors_dont_end_game() {
+		let mut bot = EndRecorder::default();
+
+		let _ = end_game_on_lost_connection(&mut bot, SC2Result::<()>::Err("bot error".into()));
+		let _ = end_game_on_lost_connection(
+			&mut bot,
+			SC2Result::<()>::Err(SC2Error::GameCreate(String::new()).into()),
+		);
+		let _ = end_game_on_lost_connection(&mut bot, Ok(()));
+		assert_eq!(bot.game_result, None);
+		assert!(bot.ends.borrow().is_empty());
+	}
+}
@@ -0,0 +1,163 @@
+//! Simple deterministic combat simulator.
+
+use crate::{unit::Unit, units::Units};
+
+/// Duration of one simulation step in seconds.
+const STEP: f32 = 0.5;
+/// Maximum duration of simulated fight in seconds.
+const MAX_DURATION: f32 = 120.0;
+
+/// Result of [`simulate_combat`](crate::bot::Bot::simulate_combat).
+#[derive(Debug, Clone, Default)]
+pub struct CombatResult {
+	/// `true` if own army survived and remaining enemies can't fight back,
+	/// `false` if neither side can damage the other.
+	pub win: bool,
+	/// Tags of own units survived the fight.
+	pub my_survivors: Vec<u64>,
+	/// Tags of enemy units survived the fight.
+	pub enemy_survivors: Vec<u64>,
+	/// Supply of own survived units.
+	pub my_supply_left: f32,
+	/// Supply of enemy survived units.
+	pub enemy_supply_left: f32,
+	/// Estimated duration of the fight in seconds.
+	pub duration: f32,
+}
+
+struct Fighter<'a> {
+	unit: &'a Unit,
+	hits: f32,
+}
+impl Fighter<'_> {
+	fn is_alive(&self) -> bool {
+		self.hits > 0.0
+	}
+}
+
+fn fighters(units: &Units) -> Vec<Fighter> {
+	units
+		.iter()
+		.filter_map(|u| {
+			let hits = u.hits()? as f32;
+			if hits > 0.0 {
+				Some(Fighter { unit: u, hits })
+			} else {
+				None
+			}
+		})
+		.collect()
+}
+
+/// DPS of every attacker against every target, computed once before simulation.
+struct DpsMatrix {
+	dps: Vec<f32>,
+	targets: usize,
+}
+impl DpsMatrix {
+	fn new(attackers: &[Fighter], targets: &[Fighter]) -> Self {
+		Self {
+			dps: attackers
+				.iter()
+				.flat_map(|a| targets.iter().map(move |t| a.unit.real_weapon_vs(t.unit).0))
+				.collect(),
+			targets: targets.len(),
+		}
+	}
+	fn get(&self, attacker: usize, target: usize) -> f32 {
+		self.dps[attacker * self.targets + target]
+	}
+}
+
+/// Returns damage dealt to each target by all attackers during one step.
+fn deal_damage(attackers: &[Fighter], targets: &[Fighter], dps: &DpsMatrix) -> Vec<f32> {
+	let mut damage = vec![0.0; targets.len()];
+	for (ai, _) in attackers.iter().enumerate().filter(|(_, a)| a.is_alive()) {
+		// Focusing target which dies the fastest, ignoring ones already killed on this step
+		let target = targets
+			.iter()
+			.enumerate()
+			.filter_map(|(i, t)| {
+				let hits_left = t.hits - damage[i];
+				if hits_left <= 0.0 {
+					return None;
+				}
+				let dps = dps.get(ai, i);
+				if dps > 0.0 {
+					Some((i, dps, hits_left / dps))
+				} else {
+					None
+				}
+			})
+			.min_by(|(_, _, t1), (_, _, t2)| t1.partial_cmp(t2).unwrap());
+
+		if let Some((i, dps, _)) = target {
+			damage[i] += dps * STEP;
+		}
+	}
+	damage
+}
+
+fn apply_damage(targets: &mut [Fighter], damage: &[f32]) {
+	for (t, d) in targets.iter_mut().zip(damage) {
+		t.hits -= d;
+	}
+}
+
+fn can_fight(attackers: &[Fighter], targets: &[Fighter], dps: &DpsMatrix) -> bool {
+	attackers
+		.iter()
+		.enumerate()
+		.filter(|(_, a)| a.is_alive())
+		.any(|(ai, _)| {
+			targets
+				.iter()
+				.enumerate()
+				.filter(|(_, t)| t.is_alive())
+				.any(|(ti, _)| dps.get(ai, ti) > 0.0)
+		})
+}
+
+pub(crate) fn simulate(my: &Units, enemy: &Units) -> CombatResult {
+	let mut my = fighters(my);
+	let mut enemy = fighters(enemy);
+	let my_dps = DpsMatrix::new(&my, &enemy);
+	let enemy_dps = DpsMatrix::new(&enemy, &my);
+
+	let mut duration = 0.0;
+	while duration < MAX_DURATION && (can_fight(&my, &enemy, &my_dps) || can_fight(&enemy, &my, &enemy_dps)) {
+		let damage_to_enemy = deal_damage(&my, &enemy, &my_dps);
+		let damage_to_my = deal_damage(&enemy, &my, &enemy_dps);
+		apply_damage(&mut enemy, &damage_to_enemy);
+		apply_damage(&mut my, &damage_to_my);
+		duration += STEP;
+	}
+
+	let survivors = |side: &[Fighter]| {
+		side.iter()
+			.filter(|f| f.is_alive())
+			.map(|f| f.unit.tag())
+			.collect::<Vec<u64>>()
+	};
+	let supply_left = |side: &[Fighter]| {
+		side.iter()
+			.filter(|f| f.is_alive())
+			.map(|f| f.unit.supply_cost())
+			.sum::<f32>()
+	};
+
+	let my_survivors = survivors(&my);
+	let enemy_survivors = survivors(&enemy);
+	// Not a win when neither side can damage the other
+	let win = !my_survivors.is_empty()
+		&& !can_fight(&enemy, &my, &enemy_dps)
+		&& (enemy_survivors.is_empty() || can_fight(&my, &enemy, &my_dps));
+	CombatResult {
+		win,
+		my_survivors,
+		enemy_survivors,
+		my_supply_left: supply_left(&my),
+		enemy_supply_left: supply_left(&enemy),
+		duration,
+	}
+}
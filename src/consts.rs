@@ -6,7 +6,7 @@ use crate::{
 	ids::*,
 	player::Race,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Default in-game speed modifier (on **Faster** game speed).
 /// See [page on liquipedia](https://liquipedia.net/starcraft2/Game_Speed) for more info.
@@ -14,6 +14,14 @@ pub const GAME_SPEED: f32 = 1.4;
 /// Frames per second, calculated by `16 (default frames per second) * 1.4 (game speed)`.
 pub const FRAMES_PER_SECOND: f32 = 22.4;
 
+/// Radius of power field provided by pylon.
+pub const PYLON_POWER_RADIUS: f32 = 6.5;
+/// Radius of power field provided by warp prism in phasing mode.
+pub const WARP_PRISM_POWER_RADIUS: f32 = 3.75;
+
+/// Turning rate (in degrees per game second) of units, which aren't listed in internal table.
+pub(crate) const DEFAULT_TURN_RATE: f32 = 999.8437;
+
 /// Units under effect of raven's anit-armor missile have this buff.
 /// It reduces armor and shield armor by 3 (armor can be negative at this point).
 // #[cfg(windows)]
@@ -34,6 +42,16 @@ pub(crate) const INHIBITOR_IDS: [UnitTypeId; 6] = [
 	UnitTypeId::InhibitorZoneFlyingMedium,
 	UnitTypeId::InhibitorZoneFlyingLarge,
 ];
+/// Abilities, which have autocast turned on when unit is created.
+///
+/// Other autocastable abilities (e.g. SCV and MULE repair, Queen transfusion)
+/// have autocast turned off by default, so they're not listed here.
+pub(crate) const AUTOCAST_ON_BY_DEFAULT: [AbilityId; 3] = [
+	AbilityId::MedivacHealHeal,
+	AbilityId::BuildInterceptors,
+	AbilityId::ShieldBatteryRechargeEx5ShieldBatteryRecharge,
+];
+
 /*#[cfg(unix)]
 pub(crate) const INHIBITOR_IDS: [UnitTypeId; 3] = [
 	UnitTypeId::InhibitorZoneSmall,
@@ -684,6 +702,13 @@ lazy_static! {
 		UpgradeId::EnhancedShockwaves => UnitTypeId::GhostAcademy,
 	];
 
+	// Structures, which produce non-worker units (townhalls excluded).
+	pub(crate) static ref PRODUCTION_STRUCTURES: HashSet<UnitTypeId> = ALL_PRODUCERS
+		.iter()
+		.filter(|(unit, _)| unit.is_unit() && !unit.is_worker())
+		.flat_map(|(_, producers)| producers.iter().copied())
+		.filter(|producer| producer.is_structure() && !producer.is_townhall())
+		.collect();
 	pub(crate) static ref DAMAGE_BONUS_PER_UPGRADE: HashMap<UnitTypeId, BonusesForTarget> = hashmap![
 		// Protoss
 		UnitTypeId::Probe => hashmap![TargetType::Ground => (Some(0), hashmap![])],
@@ -784,6 +809,52 @@ lazy_static! {
 		UnitTypeId::Adept => AbilityId::TrainWarpAdept,
 	];
 
+	/// Energy costs of abilities, which aren't provided by the API.
+	pub static ref ABILITY_ENERGY_COST: HashMap<AbilityId, u32> = hashmap![
+		// Terran
+		AbilityId::CalldownMULECalldownMULE => 50,
+		AbilityId::SupplyDropSupplyDrop => 50,
+		AbilityId::ScannerSweepScan => 50,
+		AbilityId::EffectGhostSnipe => 50,
+		AbilityId::EMPEMP => 75,
+		AbilityId::BehaviorCloakOnGhost => 25,
+		AbilityId::BehaviorCloakOnBanshee => 25,
+		AbilityId::BuildAutoTurretAutoTurret => 50,
+		AbilityId::EffectInterferenceMatrix => 75,
+		AbilityId::EffectAntiArmorMissile => 75,
+		// Zerg
+		AbilityId::EffectInjectLarva => 25,
+		AbilityId::BuildCreepTumorQueen => 25,
+		AbilityId::TransfusionTransfusion => 50,
+		AbilityId::FungalGrowthFungalGrowth => 75,
+		AbilityId::NeuralParasiteNeuralParasite => 100,
+		AbilityId::BlindingCloudBlindingCloud => 100,
+		AbilityId::EffectAbduct => 75,
+		AbilityId::ParasiticBombParasiticBomb => 125,
+		AbilityId::ContaminateContaminate => 125,
+		AbilityId::SpawnChangelingSpawnChangeling => 50,
+		// Protoss
+		AbilityId::PsiStormPsiStorm => 75,
+		AbilityId::FeedbackFeedback => 50,
+		AbilityId::GuardianShieldGuardianShield => 75,
+		AbilityId::ForceFieldForceField => 50,
+		AbilityId::HallucinationArchon => 75,
+		AbilityId::HallucinationColossus => 75,
+		AbilityId::HallucinationHighTemplar => 75,
+		AbilityId::HallucinationImmortal => 75,
+		AbilityId::HallucinationPhoenix => 75,
+		AbilityId::HallucinationProbe => 75,
+		AbilityId::HallucinationStalker => 75,
+		AbilityId::HallucinationVoidRay => 75,
+		AbilityId::HallucinationWarpPrism => 75,
+		AbilityId::HallucinationZealot => 75,
+		AbilityId::HallucinationOracle => 75,
+		AbilityId::HallucinationDisruptor => 75,
+		AbilityId::EffectChronoBoostEnergyCost => 50,
+		AbilityId::OracleRevelationOracleRevelation => 25,
+		AbilityId::OracleStasisTrapOracleBuildStasisTrap => 50,
+		AbilityId::EffectTimeWarp => 75,
+	];
 	pub(crate) static ref MISSED_WEAPONS: HashMap<UnitTypeId, Vec<Weapon>> = hashmap![
 		UnitTypeId::Baneling => vec![Weapon {
 			target: TargetType::Ground,
@@ -860,6 +931,48 @@ lazy_static! {
 			speed: 1.0,
 		}],
 	];
+	/// Splash radiuses of units' weapons, used to estimate damage dealt to groups of units.
+	/// Values are approximate, for line splashes (Hellion, Colossus) average radius is used.
+	pub static ref SPLASH_RADIUS: HashMap<UnitTypeId, f32> = hashmap![
+		UnitTypeId::Baneling => 2.2,
+		UnitTypeId::SiegeTankSieged => 1.25,
+		UnitTypeId::PlanetaryFortress => 1.25,
+		UnitTypeId::Colossus => 1.4,
+		UnitTypeId::Hellion => 1.0,
+		UnitTypeId::HellionTank => 2.0,
+		UnitTypeId::Archon => 1.0,
+		UnitTypeId::Ultralisk => 2.0,
+		UnitTypeId::WidowMineBurrowed => 1.75,
+		UnitTypeId::Thor => 0.5,
+		UnitTypeId::Liberator => 1.5,
+		UnitTypeId::LurkerMPBurrowed => 1.0,
+	];
+	/// Durations (in seconds) of effects, which disappear after some time.
+	/// For corrosive bile and nuke it's time until they land.
+	pub static ref EFFECT_DURATIONS: HashMap<EffectId, f32> = hashmap![
+		EffectId::PsiStormPersistent => 2.85,
+		EffectId::GuardianShieldPersistent => 5.71,
+		EffectId::ScannerSweep => 8.57,
+		EffectId::NukePersistent => 14.0,
+		EffectId::BlindingCloudCP => 5.71,
+		EffectId::RavagerCorrosiveBileCP => 2.5,
+	];
+	/// Lift abilities of terran buildings mapped to their ids.
+	pub static ref LIFT_ABILITIES: HashMap<UnitTypeId, AbilityId> = hashmap![
+		UnitTypeId::CommandCenter => AbilityId::LiftCommandCenter,
+		UnitTypeId::OrbitalCommand => AbilityId::LiftOrbitalCommand,
+		UnitTypeId::Barracks => AbilityId::LiftBarracks,
+		UnitTypeId::Factory => AbilityId::LiftFactory,
+		UnitTypeId::Starport => AbilityId::LiftStarport,
+	];
+	/// Land abilities of flying terran buildings mapped to their ids.
+	pub static ref LAND_ABILITIES: HashMap<UnitTypeId, AbilityId> = hashmap![
+		UnitTypeId::CommandCenterFlying => AbilityId::LandCommandCenter,
+		UnitTypeId::OrbitalCommandFlying => AbilityId::LandOrbitalCommand,
+		UnitTypeId::BarracksFlying => AbilityId::LandBarracks,
+		UnitTypeId::FactoryFlying => AbilityId::LandFactory,
+		UnitTypeId::StarportFlying => AbilityId::LandStarport,
+	];
 	/// Radiuses of Inhibitor Zones mapped to their ids.
 	pub static ref INHIBITOR_ZONE_RADIUS: HashMap<UnitTypeId, f32> = hashmap![
 		UnitTypeId::InhibitorZoneSmall => 4.0,
@@ -869,6 +982,27 @@ lazy_static! {
 		UnitTypeId::InhibitorZoneFlyingMedium => 5.0,
 		UnitTypeId::InhibitorZoneFlyingLarge => 6.0,
 	];
+	// Turning rates in degrees per game second (as in game editor), units not listed turn almost instantly.
+	pub(crate) static ref TURN_RATES: HashMap<UnitTypeId, f32> = hashmap![
+		UnitTypeId::SiegeTank => 360.0,
+		UnitTypeId::Thor => 360.0,
+		UnitTypeId::ThorAP => 360.0,
+		UnitTypeId::Cyclone => 720.0,
+		UnitTypeId::Hellion => 720.0,
+		UnitTypeId::HellionTank => 720.0,
+		UnitTypeId::WidowMine => 720.0,
+		UnitTypeId::Battlecruiser => 360.0,
+		UnitTypeId::Liberator => 720.0,
+		UnitTypeId::Colossus => 360.0,
+		UnitTypeId::Immortal => 720.0,
+		UnitTypeId::Archon => 720.0,
+		UnitTypeId::Carrier => 360.0,
+		UnitTypeId::Tempest => 360.0,
+		UnitTypeId::Mothership => 360.0,
+		UnitTypeId::Ultralisk => 720.0,
+		UnitTypeId::BroodLord => 360.0,
+		UnitTypeId::SwarmHostMP => 720.0,
+	];
 	pub(crate) static ref SPEED_BUFFS: HashMap<BuffId, f32> = hashmap![
 		BuffId::Stimpack => 1.5,
 		BuffId::StimpackMarauder => 1.5,
@@ -3,6 +3,7 @@
 use crate::{
 	geometry::{Point2, Point3},
 	ids::UnitTypeId,
+	player::GameResult,
 	IntoProto,
 };
 use num_traits::ToPrimitive;
@@ -15,15 +16,27 @@ use sc2_proto::debug::{
 type Color = (u32, u32, u32);
 type ScreenPos = (f32, f32);
 
+/// Amount of resources given by single [`cheat_minerals`](Debugger::cheat_minerals)
+/// or [`cheat_gas`](Debugger::cheat_gas) command.
+const CHEAT_RESOURCES_AMOUNT: u32 = 5000;
+
 /// Helper struct for interacting with Debug API.
 /// Can be accessed through [`debug`] field of bot.
 ///
+/// Debug commands work only in local games and are ignored on ladder.
+///
+/// Cheats like [`cheat_resources`](Self::cheat_resources) or [`cheat_fast_build`](Self::cheat_fast_build)
+/// can be used to test build orders without waiting.
+/// Note that API can't set exact amount of resources, it only gives 5000 of them per command.
+///
 /// [`debug`]: crate::bot::Bot::debug
 #[derive(Default)]
 pub struct Debugger {
 	debug_commands: Vec<DebugCommand>,
 	debug_drawings: Vec<DebugDraw>,
 	kill_tags: FxHashSet<u64>,
+	map_revealed: bool,
+	fast_build_enabled: bool,
 }
 impl Debugger {
 	pub(crate) fn get_commands(&mut self) -> &[DebugCommand] {
@@ -89,10 +102,21 @@ impl Debugger {
 				.map(|(type_id, owner, pos, count)| DebugCommand::CreateUnit(type_id, owner, pos, count)),
 		);
 	}
+	/// Spawns `count` units of given type for given owner's player id at given position.
+	///
+	/// Single-command version of [`create_units`](Self::create_units).
+	pub fn create_unit(&mut self, type_id: UnitTypeId, owner: Option<u32>, pos: Point2, count: u32) {
+		self.debug_commands
+			.push(DebugCommand::CreateUnit(type_id, owner, pos, count));
+	}
 	/// Kills units with given tags.
 	pub fn kill_units<'a, T: IntoIterator<Item = &'a u64>>(&mut self, tags: T) {
 		self.kill_tags.extend(tags);
 	}
+	/// Kills unit with given tag.
+	pub fn kill_unit(&mut self, tag: u64) {
+		self.kill_tags.insert(tag);
+	}
 	/// Sets values for units using given commands in format: (unit tag, value type, value).
 	pub fn set_unit_values<'a, T>(&mut self, cmds: T)
 	where
@@ -104,6 +128,13 @@ impl Debugger {
 				.map(|(tag, unit_value, value)| DebugCommand::SetUnitValue(tag, unit_value, value)),
 		);
 	}
+	/// Sets value of given type for unit with given tag.
+	///
+	/// Single-command version of [`set_unit_values`](Self::set_unit_values).
+	pub fn set_unit_value(&mut self, tag: u64, unit_value: UnitValue, value: u32) {
+		self.debug_commands
+			.push(DebugCommand::SetUnitValue(tag, unit_value, value));
+	}
 	/// Ends game with Victory for bot
 	pub fn win_game(&mut self) {
 		self.debug_commands.push(DebugCommand::EndGame(true));
@@ -112,11 +143,53 @@ impl Debugger {
 	pub fn end_game(&mut self) {
 		self.debug_commands.push(DebugCommand::EndGame(false));
 	}
-	/// Disables fog of war, makes all map visible
+	/// Ends game with given result for bot.
+	///
+	/// Only [`Victory`](GameResult::Victory) and [`Defeat`](GameResult::Defeat) are supported by API,
+	/// other results are ignored.
+	///
+	/// Returns `true` if command was sent.
+	pub fn end_game_with(&mut self, result: GameResult) -> bool {
+		match result {
+			GameResult::Victory => self.win_game(),
+			GameResult::Defeat => self.end_game(),
+			_ => {
+				warn!("Can't end game with result {:?} through debug", result);
+				return false;
+			}
+		}
+		true
+	}
+	/// Sets score of the bot (only for curriculum maps with score).
+	pub fn set_score(&mut self, score: f32) {
+		self.debug_commands.push(DebugCommand::SetScore(score));
+	}
+	/// Disables fog of war, makes all map visible.
+	/// Using it again enables fog of war back.
 	pub fn show_map(&mut self) {
+		self.map_revealed = !self.map_revealed;
 		self.debug_commands
 			.push(DebugCommand::GameState(DebugGameState::ShowMap));
 	}
+	/// Makes all map visible, does nothing if map is already revealed.
+	///
+	/// Unlike [`show_map`](Self::show_map) can be safely called multiple times.
+	pub fn reveal_map(&mut self) {
+		if !self.map_revealed {
+			self.show_map();
+		}
+	}
+	/// Enables fog of war back, if map was revealed with [`reveal_map`](Self::reveal_map)
+	/// or [`show_map`](Self::show_map).
+	pub fn hide_map(&mut self) {
+		if self.map_revealed {
+			self.show_map();
+		}
+	}
+	/// Checks if map is currently revealed through debug.
+	pub fn is_map_revealed(&self) -> bool {
+		self.map_revealed
+	}
 	/// Gives ability to control enemy units
 	pub fn control_enemy(&mut self) {
 		self.debug_commands
@@ -173,11 +246,35 @@ impl Debugger {
 		self.debug_commands
 			.push(DebugCommand::GameState(DebugGameState::Upgrade));
 	}
-	/// Significantly increases speed of making units, structures and upgrades
+	/// Significantly increases speed of making units, structures and upgrades.
+	/// Using it again disables fast build.
 	pub fn cheat_fast_build(&mut self) {
+		self.fast_build_enabled = !self.fast_build_enabled;
 		self.debug_commands
 			.push(DebugCommand::GameState(DebugGameState::FastBuild));
 	}
+	/// Enables fast build, does nothing if it's already enabled.
+	///
+	/// Unlike [`cheat_fast_build`](Self::cheat_fast_build) can be safely called multiple times.
+	/// Works only in local games.
+	pub fn fast_build(&mut self) {
+		if !self.fast_build_enabled {
+			self.cheat_fast_build();
+		}
+	}
+	/// Gives bot at least given amount of minerals and vespene.
+	///
+	/// API can't set exact amount of resources, so they're given in portions of 5000
+	/// (e.g. `set_resources(12000, 0)` gives 15000 minerals) and added to current ones.
+	/// Works only in local games.
+	pub fn set_resources(&mut self, minerals: u32, vespene: u32) {
+		for _ in 0..minerals.div_ceil(CHEAT_RESOURCES_AMOUNT) {
+			self.cheat_minerals();
+		}
+		for _ in 0..vespene.div_ceil(CHEAT_RESOURCES_AMOUNT) {
+			self.cheat_gas();
+		}
+	}
 }
 
 #[derive(Debug, Clone)]
@@ -187,7 +284,7 @@ pub(crate) enum DebugCommand {
 	CreateUnit(UnitTypeId, Option<u32>, Point2, u32),
 	KillUnit(Vec<u64>),
 	// TestProcess,
-	// SetScore,
+	SetScore(f32),
 	EndGame(bool),
 	SetUnitValue(u64, UnitValue, u32),
 }
@@ -213,6 +310,7 @@ impl IntoProto<ProtoDebugCommand> for &DebugCommand {
 					end_game.set_end_result(DebugEndGame_EndResult::DeclareVictory);
 				}
 			}
+			DebugCommand::SetScore(score) => proto.mut_score().set_score(*score),
 			DebugCommand::SetUnitValue(tag, unit_value, value) => {
 				let cmd = proto.mut_unit_value();
 				cmd.set_unit_tag(*tag);
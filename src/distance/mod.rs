@@ -25,6 +25,22 @@ pub trait Distance: Into<Point2> {
 	fn distance<P: Into<Point2>>(self, other: P) -> f32 {
 		self.distance_squared(other).sqrt()
 	}
+	/// Calculates manhattan distance (sum of absolute differences of coordinates)
+	/// from `self` to `other`.
+	fn distance_manhattan<P: Into<Point2>>(self, other: P) -> f32 {
+		let a = self.into();
+		let b = other.into();
+
+		(a.x - b.x).abs() + (a.y - b.y).abs()
+	}
+	/// Calculates chebyshev distance (maximum of absolute differences of coordinates)
+	/// from `self` to `other`.
+	fn distance_chebyshev<P: Into<Point2>>(self, other: P) -> f32 {
+		let a = self.into();
+		let b = other.into();
+
+		(a.x - b.x).abs().max((a.y - b.y).abs())
+	}
 	/// Checks if distance between `self` and `other` is less than given `distance`.
 	#[inline]
 	fn is_closer<P: Into<Point2>>(self, distance: f32, other: P) -> bool {
@@ -67,6 +83,10 @@ where
 	fn further<T: Into<Point2>>(self, distance: f32, target: T) -> Further<Self> {
 		Further::new(self, distance, target.into())
 	}
+	/// Filters all items with distance to `target` in range from `min` to `max` (inclusive).
+	fn in_distance_band<T: Into<Point2>>(self, min: f32, max: f32, target: T) -> InDistanceBand<Self> {
+		InDistanceBand::new(self, min, max, target.into())
+	}
 
 	/// Returns closest to `target` item in iterator.
 	fn closest<T: Into<Point2>>(self, target: T) -> Option<Self::Item> {
@@ -88,6 +108,14 @@ where
 		self.furthest_distance_squared(target).map(|dist| dist.sqrt())
 	}
 
+	/// Returns closest to `target` item in iterator together with distance to it.
+	fn closest_with_distance<T: Into<Point2>>(self, target: T) -> Option<(Self::Item, f32)> {
+		let target = target.into();
+		self.map(|u| (u, u.distance_squared(target)))
+			.min_by(|(_, d1), (_, d2)| cmp(d1, d2))
+			.map(|(u, dist)| (u, dist.sqrt()))
+	}
+
 	/// Returns squared distance to closest to `target` item in iterator.
 	fn closest_distance_squared<T: Into<Point2>>(self, target: T) -> Option<f32> {
 		let target = target.into();
@@ -303,3 +331,62 @@ impl<I> Further<I> {
 	}
 }
 impl_simple_iterator!(Further);
+
+/// An iterator that filters items with distance to target in given range.
+#[derive(Clone)]
+pub struct InDistanceBand<I> {
+	iter: I,
+	min: f32,
+	max: f32,
+	target: Point2,
+}
+impl<I> InDistanceBand<I> {
+	fn new(iter: I, min: f32, max: f32, target: Point2) -> Self {
+		Self {
+			iter,
+			min,
+			max,
+			target,
+		}
+	}
+
+	fn predicate<T: Distance + Copy>(&self) -> impl Fn(&T) -> bool {
+		let min = self.min * self.min;
+		let max = self.max * self.max;
+		let target = self.target;
+		move |u| {
+			let distance = u.distance_squared(target);
+			min <= distance && distance <= max
+		}
+	}
+}
+impl_simple_iterator!(InDistanceBand);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn manhattan_distance() {
+		let a = Point2::new(1.0, 2.0);
+		assert_eq!(a.distance_manhattan(Point2::new(4.0, -2.0)), 7.0);
+		assert_eq!(a.distance_manhattan(a), 0.0);
+		assert_eq!((4.0, -2.0).distance_manhattan(a), 7.0);
+	}
+
+	#[test]
+	fn chebyshev_distance() {
+		let a = Point2::new(1.0, 2.0);
+		assert_eq!(a.distance_chebyshev(Point2::new(4.0, -2.0)), 4.0);
+		assert_eq!(a.distance_chebyshev(Point2::new(-5.0, 3.0)), 6.0);
+		assert_eq!((4.0, -2.0).distance_chebyshev(a), 4.0);
+	}
+
+	#[test]
+	fn distances_are_ordered() {
+		let a = Point2::new(3.0, 7.0);
+		let b = Point2::new(-2.0, 1.5);
+		assert!(a.distance_chebyshev(b) <= a.distance(b));
+		assert!(a.distance(b) <= a.distance_manhattan(b));
+	}
+}
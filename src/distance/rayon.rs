@@ -17,6 +17,10 @@ where
 	fn further<T: Into<Point2>>(self, distance: f32, target: T) -> Further<Self> {
 		Further::new(self, distance, target.into())
 	}
+	/// Filters all items with distance to `target` in range from `min` to `max` (inclusive).
+	fn in_distance_band<T: Into<Point2>>(self, min: f32, max: f32, target: T) -> InDistanceBand<Self> {
+		InDistanceBand::new(self, min, max, target.into())
+	}
 
 	/// Returns closest to `target` item in iterator.
 	fn closest<T: Into<Point2>>(self, target: T) -> Option<Self::Item> {
@@ -38,6 +42,14 @@ where
 		self.furthest_distance_squared(target).map(|dist| dist.sqrt())
 	}
 
+	/// Returns closest to `target` item in iterator together with distance to it.
+	fn closest_with_distance<T: Into<Point2>>(self, target: T) -> Option<(Self::Item, f32)> {
+		let target = target.into();
+		self.map(|u| (u, u.distance_squared(target)))
+			.min_by(|(_, d1), (_, d2)| cmp(d1, d2))
+			.map(|(u, dist)| (u, dist.sqrt()))
+	}
+
 	/// Returns squared distance to closest to `target` item in iterator.
 	fn closest_distance_squared<T: Into<Point2>>(self, target: T) -> Option<f32> {
 		let target = target.into();
@@ -303,3 +315,33 @@ impl<I> Further<I> {
 	}
 }
 impl_simple_iterator!(Further);
+
+/// An iterator that filters items with distance to target in given range.
+#[derive(Clone)]
+pub struct InDistanceBand<I> {
+	iter: I,
+	min: f32,
+	max: f32,
+	target: Point2,
+}
+impl<I> InDistanceBand<I> {
+	fn new(iter: I, min: f32, max: f32, target: Point2) -> Self {
+		Self {
+			iter,
+			min,
+			max,
+			target,
+		}
+	}
+
+	fn predicate<T: Distance + Copy>(&self) -> impl Fn(&T) -> bool {
+		let min = self.min * self.min;
+		let max = self.max * self.max;
+		let target = self.target;
+		move |u| {
+			let distance = u.distance_squared(target);
+			min <= distance && distance <= max
+		}
+	}
+}
+impl_simple_iterator!(InDistanceBand);
@@ -0,0 +1,60 @@
+//! Recorder of game events and resource snapshots in JSON lines format.
+
+use crate::{bot::Bot, Event};
+use serde::Serialize;
+use std::{
+	fs::{File, OpenOptions},
+	io::{self, BufWriter, Write},
+	path::Path,
+};
+
+/// Number of recorded steps between flushes of the log file.
+const FLUSH_INTERVAL: usize = 100;
+
+#[derive(Serialize)]
+struct StepRecord<'a> {
+	game_loop: u32,
+	time: f32,
+	minerals: u32,
+	vespene: u32,
+	supply_used: u32,
+	supply_cap: u32,
+	events: &'a [Event],
+}
+
+pub(crate) struct EventLog {
+	writer: BufWriter<File>,
+	unflushed: usize,
+}
+impl EventLog {
+	pub(crate) fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+		let file = OpenOptions::new().create(true).append(true).open(path)?;
+		Ok(Self {
+			writer: BufWriter::new(file),
+			unflushed: 0,
+		})
+	}
+	pub(crate) fn record(&mut self, bot: &Bot, events: &[Event]) -> io::Result<()> {
+		let record = StepRecord {
+			game_loop: bot.state.observation.game_loop(),
+			time: bot.time,
+			minerals: bot.minerals,
+			vespene: bot.vespene,
+			supply_used: bot.supply_used,
+			supply_cap: bot.supply_cap,
+			events,
+		};
+		serde_json::to_writer(&mut self.writer, &record)?;
+		self.writer.write_all(b"\n")?;
+
+		self.unflushed += 1;
+		if self.unflushed >= FLUSH_INTERVAL {
+			self.flush()?;
+		}
+		Ok(())
+	}
+	pub(crate) fn flush(&mut self) -> io::Result<()> {
+		self.unflushed = 0;
+		self.writer.flush()
+	}
+}
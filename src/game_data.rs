@@ -2,6 +2,7 @@
 #![allow(missing_docs)]
 
 use crate::{
+	consts::ABILITY_ENERGY_COST,
 	ids::{AbilityId, BuffId, EffectId, UnitTypeId, UpgradeId},
 	player::Race,
 	FromProto, TryFromProto,
@@ -18,6 +19,10 @@ use sc2_proto::{
 };
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::{
+	iter::Sum,
+	ops::{Add, AddAssign},
+};
 
 /// All the data about different ids stored here.
 /// Can be accessed through [`game_data`](crate::bot::Bot::game_data) field.
@@ -66,9 +71,39 @@ impl FromProto<ResponseData> for GameData {
 		}
 	}
 }
+impl GameData {
+	/// Returns time needed to build given unit type (in game loops).
+	pub fn build_time(&self, unit: UnitTypeId) -> Option<f32> {
+		self.units.get(&unit).map(|data| data.build_time)
+	}
+	/// Returns information about given ability if it's present in game data.
+	pub fn ability_data(&self, ability: AbilityId) -> Option<&AbilityData> {
+		self.abilities.get(&ability)
+	}
+	/// Returns energy cost of given ability or `None` if ability doesn't cost energy.
+	pub fn ability_energy_cost(&self, ability: AbilityId) -> Option<u32> {
+		match self.abilities.get(&ability) {
+			Some(data) => data.energy_cost,
+			None => ABILITY_ENERGY_COST.get(&ability).copied(),
+		}
+	}
+	/// Returns resource cost of given ability, if it's used to produce unit or research upgrade.
+	pub fn ability_cost(&self, ability: AbilityId) -> Option<Cost> {
+		self.units
+			.values()
+			.find(|data| data.ability == Some(ability))
+			.map(|data| data.cost())
+			.or_else(|| {
+				self.upgrades
+					.values()
+					.find(|data| data.ability == ability)
+					.map(|data| data.cost())
+			})
+	}
+}
 
 /// Cost of an item (`UnitTypeId` or `UpgradeId`) in resources, supply and time.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Cost {
 	pub minerals: u32,
@@ -76,6 +111,28 @@ pub struct Cost {
 	pub supply: f32,
 	pub time: f32,
 }
+impl Add for Cost {
+	type Output = Self;
+
+	fn add(self, other: Self) -> Self {
+		Self {
+			minerals: self.minerals + other.minerals,
+			vespene: self.vespene + other.vespene,
+			supply: self.supply + other.supply,
+			time: self.time + other.time,
+		}
+	}
+}
+impl AddAssign for Cost {
+	fn add_assign(&mut self, other: Self) {
+		*self = *self + other;
+	}
+}
+impl Sum for Cost {
+	fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+		iter.fold(Default::default(), Add::add)
+	}
+}
 
 /// Possible target of ability, needed when giving commands to units.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -213,11 +270,15 @@ pub struct AbilityData {
 	pub is_instant_placement: bool,
 	/// Maximum range to target of the ability.
 	pub cast_range: Option<f32>,
+	/// Energy needed to use the ability.
+	/// Not provided by the API, taken from [`ABILITY_ENERGY_COST`](crate::consts::ABILITY_ENERGY_COST).
+	pub energy_cost: Option<u32>,
 }
 impl TryFromProto<&ProtoAbilityData> for AbilityData {
 	fn try_from_proto(a: &ProtoAbilityData) -> Option<Self> {
+		let id = AbilityId::from_u32(a.get_ability_id())?;
 		Some(Self {
-			id: AbilityId::from_u32(a.get_ability_id())?,
+			id,
 			link_name: a.get_link_name().to_string(),
 			link_index: a.get_link_index(),
 			button_name: a.button_name.as_ref().cloned(),
@@ -232,6 +293,7 @@ impl TryFromProto<&ProtoAbilityData> for AbilityData {
 			footprint_radius: a.footprint_radius,
 			is_instant_placement: a.get_is_instant_placement(),
 			cast_range: a.cast_range,
+			energy_cost: ABILITY_ENERGY_COST.get(&id).copied(),
 		})
 	}
 }
@@ -254,6 +316,7 @@ pub struct UnitTypeData {
 	pub ability: Option<AbilityId>,
 	/// Race of unit.
 	pub race: Race,
+	/// Time needed to build the unit (in game loops).
 	pub build_time: f32,
 	/// Unit contains vespene (i.e. is vespene geyser).
 	pub has_vespene: bool,
@@ -3,6 +3,7 @@
 use crate::{
 	action::{Action, ActionError},
 	bot::{Bot, LockOwned, LockU32, Locked, Rs, Rw},
+	consts::{EFFECT_DURATIONS, FRAMES_PER_SECOND},
 	geometry::Point2,
 	ids::*,
 	pixel_map::{PixelMap, VisibilityMap},
@@ -18,6 +19,8 @@ use sc2_proto::{
 	raw::{Alliance as ProtoAlliance, PowerSource as ProtoPowerSource},
 	sc2api::{Alert as ProtoAlert, Request, ResponseObservation},
 };
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::ops::{Deref, DerefMut};
 
 /// Information about current state on current step.
@@ -35,6 +38,13 @@ pub struct GameState {
 	/// Messeges in game chat.
 	pub chat: Vec<ChatMessage>,
 }
+impl GameState {
+	/// Tags of units which died last step.
+	/// Shortcut for [`observation.raw.dead_units`](RawData::dead_units).
+	pub fn dead_units(&self) -> &[u64] {
+		&self.observation.raw.dead_units
+	}
+}
 
 pub(crate) fn update_state<B>(
 	bot: &mut B,
@@ -116,18 +126,33 @@ where
 		.map(PsionicMatrix::from_proto)
 		.collect();
 	raw.camera = Point2::from_proto(raw_player.get_camera());
+	let game_loop = obs.game_loop.get_locked();
+	let last_effects = std::mem::take(&mut raw.effects);
 	raw.effects = res_raw
 		.get_effects()
 		.iter()
-		.map(|e| Effect {
-			id: {
+		.map(|e| {
+			let id = {
 				let id = e.get_effect_id();
 				EffectId::from_u32(id).unwrap_or_else(|| panic!("There's no `EffectId` with value {}", id))
-			},
-			positions: e.get_pos().iter().map(Point2::from_proto).collect(),
-			alliance: Alliance::from_proto(e.get_alliance()),
-			owner: e.get_owner() as u32,
-			radius: e.get_radius(),
+			};
+			let positions = e.get_pos().iter().map(Point2::from_proto).collect::<Vec<_>>();
+			let owner = e.get_owner() as u32;
+			let first_seen = last_effects
+				.iter()
+				.find(|last| last.id == id && last.owner == owner && last.positions == positions)
+				.map_or(game_loop, |last| last.first_seen);
+			Effect {
+				id,
+				positions,
+				alliance: Alliance::from_proto(e.get_alliance()),
+				owner,
+				radius: e.get_radius(),
+				first_seen,
+				remaining_duration: EFFECT_DURATIONS.get(&id).map(|duration| {
+					(duration - game_loop.saturating_sub(first_seen) as f32 / FRAMES_PER_SECOND).max(0.0)
+				}),
+			}
 		})
 		.collect();
 	raw.radars = res_raw
@@ -150,6 +175,10 @@ where
 		let alliance = if bot.owned_tags.remove(u) {
 			bot.available_frames.write_lock().remove(u);
 			bot.under_construction.remove(u);
+			bot.commander.write_lock().autocast_state.remove(u);
+			for tags in bot.unit_labels.values_mut() {
+				tags.remove(u);
+			}
 			Some(Alliance::Own)
 		} else {
 			let removed = bot.saved_hallucinations.remove(u);
@@ -377,10 +406,33 @@ pub struct Effect {
 	pub owner: u32,
 	/// Additional radius covered by effect around every it's position.
 	pub radius: f32,
+	/// Game loop on which effect was first seen.
+	pub first_seen: u32,
+	/// Approximate time in seconds until effect disappears or lands,
+	/// `None` for effects without fixed duration (e.g. liberator zone).
+	///
+	/// Not provided by API, estimated from the time effect was first seen.
+	pub remaining_duration: Option<f32>,
+}
+impl Effect {
+	/// Checks if effect deals damage to units in it's area
+	/// (i.e. psi storm, corrosive bile, liberator zone, nuke or lurker spines).
+	pub fn is_dangerous(&self) -> bool {
+		matches!(
+			self.id,
+			EffectId::PsiStormPersistent
+				| EffectId::RavagerCorrosiveBileCP
+				| EffectId::LiberatorTargetMorphDelayPersistent
+				| EffectId::LiberatorTargetMorphPersistent
+				| EffectId::NukePersistent
+				| EffectId::LurkerMP
+		)
+	}
 }
 
 /// The alliance of unit or effect to your bot.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Alliance {
 	/// Your own objects.
 	Own,
@@ -4,6 +4,8 @@
 
 use crate::{distance::Distance, unit::Radius, FromProto, IntoProto};
 use sc2_proto::common::{Point, Point2D};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::{
 	hash::{Hash, Hasher},
 	iter::Sum,
@@ -13,6 +15,7 @@ use std::{
 /// Size of 2D rectangle.
 #[allow(missing_docs)]
 #[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Size {
 	pub x: usize,
 	pub y: usize,
@@ -27,6 +30,7 @@ impl Size {
 /// Rectangle from (x0, y0) to (x1, y1).
 #[allow(missing_docs)]
 #[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Rect {
 	pub x0: usize,
 	pub y0: usize,
@@ -43,6 +47,7 @@ impl Rect {
 /// Point on 2D grid, the most frequently used geometric primitive.
 #[allow(missing_docs)]
 #[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Point2 {
 	pub x: f32,
 	pub y: f32,
@@ -187,6 +192,20 @@ impl Point2 {
 			self.offset(-1.0, 1.0),
 		]
 	}
+	/// Returns coordinates of grid tile, which contains the point.
+	///
+	/// Coordinates are truncated (not rounded), same as in `Into<(usize, usize)>` conversion.
+	#[inline]
+	pub fn to_tile(self) -> (usize, usize) {
+		self.into()
+	}
+	/// Returns center of grid tile with given coordinates (i.e. `x + 0.5`, `y + 0.5`).
+	///
+	/// Inverse of [`to_tile`](Self::to_tile).
+	#[inline]
+	pub fn from_tile(x: usize, y: usize) -> Self {
+		(x, y).into()
+	}
 	/// Returns tuple with point's coordinates.
 	pub fn as_tuple(self) -> (f32, f32) {
 		(self.x, self.y)
@@ -436,6 +455,7 @@ impl IntoProto<Point2D> for Point2 {
 /// Point in 3D game world.
 #[allow(missing_docs)]
 #[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Point3 {
 	pub x: f32,
 	pub y: f32,
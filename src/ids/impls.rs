@@ -1,4 +1,5 @@
 use super::{AbilityId, UnitTypeId};
+use crate::consts::PRODUCTION_STRUCTURES;
 
 impl UnitTypeId {
 	#[inline]
@@ -234,6 +235,47 @@ impl UnitTypeId {
 			| UnitTypeId::Mothership
 		)
 	}
+	/// Checks if it's army unit (i.e. not worker, structure, larva, egg, cocoon, overlord, overseer, changeling
+	/// or temporary unit like Broodling, Locust, Interceptor, MULE and Auto-Turret).
+	#[rustfmt::skip::macros(matches)]
+	#[inline]
+	pub fn is_army_unit(self) -> bool {
+		self.is_unit()
+			&& !self.is_worker()
+			&& !matches!(
+				self,
+				UnitTypeId::Larva
+				| UnitTypeId::Egg
+				| UnitTypeId::BanelingCocoon
+				| UnitTypeId::RavagerCocoon
+				| UnitTypeId::LurkerMPEgg
+				| UnitTypeId::BroodLordCocoon
+				| UnitTypeId::Overlord
+				| UnitTypeId::OverlordCocoon
+				| UnitTypeId::OverlordTransport
+				| UnitTypeId::TransportOverlordCocoon
+				| UnitTypeId::Overseer
+				| UnitTypeId::OverseerSiegeMode
+				| UnitTypeId::Changeling
+				| UnitTypeId::ChangelingZealot
+				| UnitTypeId::ChangelingMarine
+				| UnitTypeId::ChangelingMarineShield
+				| UnitTypeId::ChangelingZergling
+				| UnitTypeId::ChangelingZerglingWings
+				| UnitTypeId::Broodling
+				| UnitTypeId::LocustMP
+				| UnitTypeId::LocustMPFlying
+				| UnitTypeId::Interceptor
+				| UnitTypeId::MULE
+				| UnitTypeId::AutoTurret
+			)
+	}
+	/// Checks if it's structure producing army units (e.g. Barracks, Gateway, Stargate),
+	/// townhalls are not counted. Derived from [`ALL_PRODUCERS`](crate::consts::ALL_PRODUCERS).
+	#[inline]
+	pub fn is_production_structure(self) -> bool {
+		PRODUCTION_STRUCTURES.contains(&self)
+	}
 }
 
 impl AbilityId {
@@ -351,3 +393,52 @@ impl AbilityId {
 		)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn army_units() {
+		for unit in [
+			UnitTypeId::Marine,
+			UnitTypeId::SiegeTankSieged,
+			UnitTypeId::Zergling,
+			UnitTypeId::Baneling,
+			UnitTypeId::BroodLord,
+			UnitTypeId::Queen,
+			UnitTypeId::Stalker,
+			UnitTypeId::Carrier,
+		] {
+			assert!(unit.is_army_unit(), "{:?} should be army unit", unit);
+		}
+	}
+
+	#[test]
+	fn non_army_units() {
+		for unit in [
+			UnitTypeId::SCV,
+			UnitTypeId::Drone,
+			UnitTypeId::Probe,
+			UnitTypeId::MULE,
+			UnitTypeId::AutoTurret,
+			UnitTypeId::Barracks,
+			UnitTypeId::Larva,
+			UnitTypeId::Egg,
+			UnitTypeId::BanelingCocoon,
+			UnitTypeId::RavagerCocoon,
+			UnitTypeId::LurkerMPEgg,
+			UnitTypeId::BroodLordCocoon,
+			UnitTypeId::Overlord,
+			UnitTypeId::Overseer,
+			UnitTypeId::OverseerSiegeMode,
+			UnitTypeId::Broodling,
+			UnitTypeId::LocustMP,
+			UnitTypeId::LocustMPFlying,
+			UnitTypeId::Changeling,
+			UnitTypeId::Interceptor,
+		] {
+			assert!(!unit.is_army_unit(), "{:?} shouldn't be army unit", unit);
+		}
+	}
+}
@@ -104,7 +104,7 @@ You always can do the same thing by hands if needed.
 ### Common opponent's information
 | Field                     | Type       | Description                                              |
 |---------------------------|------------|----------------------------------------------------------|
-| `self.enemy_race`         | [`Race`]   | Requested race of your opponent.                         |
+| `self.enemy_race`         | [`Race`]   | Requested race of your opponent (actual once detected).  |
 | `self.enemy_player_id`    | `u32`      | Opponent in-game id (usually `1` or `2` in 1v1 matches). |
 | `self.opponent_id`        | `String`   | Opponent id on ladder, filled in `--OpponentId`.         |
 | `self.enemy_start`        | [`Point2`] | Opponent's starting location.                            |
@@ -142,6 +142,7 @@ All fields are collections of [`Units`]:
 | `.structures`    | Structures only.                                                                                         |
 | `.townhalls`     | From all structures only townhalls here.                                                                 |
 | `.workers`       | Workers only (doesn't include MULEs).                                                                    |
+| `.mules`         | MULEs only (Populated for terrans only).                                                                 |
 | `.gas_buildings` | The gas buildings on geysers used to gather gas.                                                         |
 | `.larvas`        | Most of zerg units are morphed from it (Populated for zergs only).                                       |
 | `.placeholders`  | Kind of things that appear when you order worker to build something but construction didn't started yet. |
@@ -346,8 +347,8 @@ pub mod prelude {
 		action::Target,
 		bot::PlacementOptions,
 		client::{
-			run_ladder_game, run_vs_computer, run_vs_human, LaunchOptions, RunnerMulti, RunnerSingle,
-			SC2Result,
+			run_ladder_game, run_vs_bot, run_vs_computer, run_vs_human, LaunchOptions, RunnerMulti,
+			RunnerSingle, SC2Result,
 		},
 		consts::{ALL_PRODUCERS, PRODUCERS, RESEARCHERS, TECH_REQUIREMENTS},
 		distance::{Center, Distance, DistanceIterator, DistanceSlice},
@@ -357,21 +358,26 @@ pub mod prelude {
 		player::{AIBuild, Computer, Difficulty, GameResult, Race},
 		unit::Unit,
 		units::{iter::UnitsIterator, Units},
-		Event, Player, PlayerSettings,
+		CompositePlayer, Event, Player, PlayerHandler, PlayerSettings,
 	};
 	#[doc(no_inline)]
 	pub use sc2_macro::{bot, bot_new};
 }
 
 mod paths;
+#[cfg(test)]
+mod test_utils;
 
 pub mod action;
 pub mod api;
 pub mod bot;
 pub mod client;
+pub mod combat;
 pub mod consts;
 pub mod debug;
 pub mod distance;
+#[cfg(feature = "serde")]
+mod event_log;
 pub mod game_data;
 pub mod game_info;
 pub mod game_state;
@@ -385,8 +391,13 @@ pub mod unit;
 pub mod units;
 pub mod utils;
 
+use bot::Bot;
 use game_state::Alliance;
+use geometry::Size;
 use player::{GameResult, Race};
+use std::ops::{Deref, DerefMut};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /**
 `#[bot]` macro implements [`Deref`]`<Target = `[`Bot`]`>` and [`DerefMut`]`<Target = `[`Bot`]`>` for your struct.
@@ -607,7 +618,7 @@ impl MyBot {
 pub use sc2_macro::bot_new;
 
 #[doc(inline)]
-pub use client::SC2Result;
+pub use client::{SC2Error, SC2Result};
 /**
 Request to the SC2 API.
 
@@ -631,26 +642,42 @@ pub use sc2_proto::sc2api::Request;
 /// if `raw_crop_to_playable_area` is `true`, maps will be crooped to the size of
 /// [`self.game_info.playable_area`](game_info::GameInfo::playable_area).
 ///
+/// if `score` is `true`, [`self.state.observation.score`](score::Score) will be filled.
+///
+/// `feature_layer` and `render` enable corresponding spatial observations
+/// (available only through raw [`API`](api::API) responses).
+///
 /// Defaults:
 /// `name`: `None`
 /// `raw_affects_selection`: `false`
 /// `raw_crop_to_playable_area`: `false`
+/// `score`: `true`
+/// `feature_layer`: `None`
+/// `render`: `None`
+///
+/// Can be constructed with chained methods or with [`builder`](Self::builder).
+#[derive(Clone)]
 pub struct PlayerSettings<'a> {
 	pub race: Race,
 	pub name: Option<&'a str>,
 	pub raw_affects_selection: bool,
 	pub raw_crop_to_playable_area: bool,
+	pub score: bool,
+	pub feature_layer: Option<SpatialSetup>,
+	pub render: Option<SpatialSetup>,
 }
 impl<'a> PlayerSettings<'a> {
 	/// Constructs new settings with given `Race`.
 	pub fn new(race: Race) -> Self {
 		Self {
 			race,
-			name: None,
-			raw_affects_selection: false,
-			raw_crop_to_playable_area: false,
+			..Default::default()
 		}
 	}
+	/// Constructs new [`PlayerSettingsBuilder`] with default settings.
+	pub fn builder() -> PlayerSettingsBuilder<'a> {
+		PlayerSettingsBuilder::default()
+	}
 	/// Sets name of the player.
 	pub fn with_name(mut self, name: &'a str) -> Self {
 		self.name = Some(name);
@@ -674,12 +701,99 @@ impl Default for PlayerSettings<'_> {
 			name: None,
 			raw_affects_selection: false,
 			raw_crop_to_playable_area: false,
+			score: true,
+			feature_layer: None,
+			render: None,
+		}
+	}
+}
+
+/// Builder for [`PlayerSettings`].
+///
+/// # Usage
+/// ```
+/// let settings = PlayerSettings::builder()
+///     .race(Race::Zerg)
+///     .name("BotName")
+///     .raw_affects_selection(true)
+///     .build();
+/// ```
+#[derive(Default)]
+pub struct PlayerSettingsBuilder<'a>(PlayerSettings<'a>);
+impl<'a> PlayerSettingsBuilder<'a> {
+	/// Sets race of the player.
+	pub fn race(mut self, race: Race) -> Self {
+		self.0.race = race;
+		self
+	}
+	/// Sets name of the player.
+	pub fn name(mut self, name: &'a str) -> Self {
+		self.0.name = Some(name);
+		self
+	}
+	/// Sets `raw_affects_selection` to a given value.
+	pub fn raw_affects_selection(mut self, val: bool) -> Self {
+		self.0.raw_affects_selection = val;
+		self
+	}
+	/// Sets `raw_crop_to_playable_area` to a given value.
+	pub fn raw_crop_to_playable_area(mut self, val: bool) -> Self {
+		self.0.raw_crop_to_playable_area = val;
+		self
+	}
+	/// Sets `score` to a given value.
+	pub fn score(mut self, val: bool) -> Self {
+		self.0.score = val;
+		self
+	}
+	/// Enables feature layer observations with given setup.
+	pub fn feature_layer(mut self, setup: SpatialSetup) -> Self {
+		self.0.feature_layer = Some(setup);
+		self
+	}
+	/// Enables rendered observations with given setup.
+	pub fn render(mut self, setup: SpatialSetup) -> Self {
+		self.0.render = Some(setup);
+		self
+	}
+	/// Constructs [`PlayerSettings`] from the builder.
+	pub fn build(self) -> PlayerSettings<'a> {
+		self.0
+	}
+}
+
+/// Setup of spatial observations, used for
+/// [`feature_layer`](PlayerSettings::feature_layer) and [`render`](PlayerSettings::render).
+#[derive(Debug, Clone, Copy)]
+pub struct SpatialSetup {
+	/// Resolution of the screen layers.
+	pub resolution: Size,
+	/// Resolution of the minimap layers.
+	pub minimap_resolution: Size,
+	/// Width of the screen in world units (used only by feature layers).
+	pub width: f32,
+	/// Crops minimap to the size of playable area.
+	pub crop_to_playable_area: bool,
+	/// Enables layers with information not available to human player.
+	pub allow_cheating_layers: bool,
+}
+impl SpatialSetup {
+	/// Constructs new setup with given resolutions of screen and minimap.
+	pub fn new(resolution: Size, minimap_resolution: Size) -> Self {
+		Self {
+			resolution,
+			minimap_resolution,
+			width: 24.0,
+			crop_to_playable_area: false,
+			allow_cheating_layers: false,
 		}
 	}
 }
 
 /// Events that happen in game.
 /// Passed to [`on_event`](Player::on_event).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Event {
 	/// Unit died or structure destroyed (all units: your, enemy, neutral).
 	UnitDestroyed(u64, Option<Alliance>),
@@ -715,6 +829,123 @@ pub trait Player {
 	}
 }
 
+/// Part of the bot logic, which can be combined with others in [`CompositePlayer`].
+///
+/// Has the same methods as [`Player`], but all of them receive shared [`Bot`].
+pub trait PlayerHandler {
+	/// Called once on first step (i.e on game start).
+	fn on_start(&mut self, _bot: &mut Bot) -> SC2Result<()> {
+		Ok(())
+	}
+	/// Called on every game step.
+	fn on_step(&mut self, _bot: &mut Bot, _iteration: usize) -> SC2Result<()> {
+		Ok(())
+	}
+	/// Called once on last step with a result for your bot.
+	fn on_end(&self, _bot: &Bot, _result: GameResult) -> SC2Result<()> {
+		Ok(())
+	}
+	/// Called when different events happen.
+	fn on_event(&mut self, _bot: &mut Bot, _event: Event) -> SC2Result<()> {
+		Ok(())
+	}
+}
+
+/**
+Player made of multiple [`PlayerHandler`]s controlling the same [`Bot`].
+
+Handlers are called in order they were added, every handler receives all events.
+
+# Usage
+```
+struct Macro;
+impl PlayerHandler for Macro {
+    fn on_step(&mut self, bot: &mut Bot, iteration: usize) -> SC2Result<()> {
+        /* Build workers and structures here */
+        Ok(())
+    }
+}
+
+struct Micro;
+impl PlayerHandler for Micro {
+    fn on_step(&mut self, bot: &mut Bot, iteration: usize) -> SC2Result<()> {
+        /* Control army here */
+        Ok(())
+    }
+}
+
+let mut bot = CompositePlayer::new(PlayerSettings::new(Race::Terran))
+    .with_handler(Macro)
+    .with_handler(Micro);
+run_vs_computer(&mut bot, computer, map_name, LaunchOptions::default())?;
+```
+*/
+pub struct CompositePlayer<'a> {
+	bot: Bot,
+	settings: PlayerSettings<'a>,
+	handlers: Vec<Box<dyn PlayerHandler>>,
+}
+impl<'a> CompositePlayer<'a> {
+	/// Constructs new player without handlers, which will join game with given settings.
+	pub fn new(settings: PlayerSettings<'a>) -> Self {
+		Self {
+			bot: Bot::default(),
+			settings,
+			handlers: Vec::new(),
+		}
+	}
+	/// Adds handler to the end of handlers list.
+	pub fn with_handler<H: PlayerHandler + 'static>(mut self, handler: H) -> Self {
+		self.add_handler(handler);
+		self
+	}
+	/// Adds handler to the end of handlers list.
+	pub fn add_handler<H: PlayerHandler + 'static>(&mut self, handler: H) {
+		self.handlers.push(Box::new(handler));
+	}
+}
+impl Deref for CompositePlayer<'_> {
+	type Target = Bot;
+
+	fn deref(&self) -> &Bot {
+		&self.bot
+	}
+}
+impl DerefMut for CompositePlayer<'_> {
+	fn deref_mut(&mut self) -> &mut Bot {
+		&mut self.bot
+	}
+}
+impl Player for CompositePlayer<'_> {
+	fn get_player_settings(&self) -> PlayerSettings {
+		self.settings.clone()
+	}
+	fn on_start(&mut self) -> SC2Result<()> {
+		for handler in &mut self.handlers {
+			handler.on_start(&mut self.bot)?;
+		}
+		Ok(())
+	}
+	fn on_step(&mut self, iteration: usize) -> SC2Result<()> {
+		for handler in &mut self.handlers {
+			handler.on_step(&mut self.bot, iteration)?;
+		}
+		Ok(())
+	}
+	fn on_end(&self, result: GameResult) -> SC2Result<()> {
+		for handler in &self.handlers {
+			handler.on_end(&self.bot, result)?;
+		}
+		Ok(())
+	}
+	fn on_event(&mut self, event: Event) -> SC2Result<()> {
+		for handler in &mut self.handlers {
+			handler.on_event(&mut self.bot, event.clone())?;
+		}
+		Ok(())
+	}
+}
+
 trait FromProto<T>
 where
 	Self: Sized,
@@ -5,6 +5,8 @@ use crate::{geometry::Point2, FromProto};
 use ndarray::Array2;
 use num_traits::FromPrimitive;
 use sc2_proto::common::ImageData;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::{
 	fmt,
 	ops::{Index, IndexMut},
@@ -79,6 +81,7 @@ impl FromProto<&ImageData> for VisibilityMap {
 /// Base for the most 2d maps.
 #[variant_checkers]
 #[derive(FromPrimitive, ToPrimitive, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Pixel {
 	/// When pixel is set, this tile is obstacle (e.g. not pathable | not placeable)
 	/// or has something on it (e.g. has creep).
@@ -100,6 +103,7 @@ impl fmt::Debug for Pixel {
 /// Base for visibility maps.
 #[variant_checkers]
 #[derive(Debug, FromPrimitive, ToPrimitive, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Visibility {
 	/// Position is hidden (i.e. weren't explored before)
 	#[default]
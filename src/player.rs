@@ -12,6 +12,7 @@ use sc2_proto::{
 };
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// Representation of game races (your gender in SC2).
 #[variant_checkers]
@@ -137,6 +138,7 @@ impl IntoProto<ProtoAIBuild> for AIBuild {
 }
 
 /// Type of the player, used when joining a game.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum PlayerType {
 	/// Bot or Human.
@@ -166,6 +168,8 @@ impl IntoProto<ProtoPlayerType> for PlayerType {
 }
 
 /// Computer opponent configuration used in [`run_vs_computer`](crate::client::run_vs_computer).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone)]
 pub struct Computer {
 	pub race: Race,
 	pub difficulty: Difficulty,
@@ -179,6 +183,16 @@ impl Computer {
 			ai_build,
 		}
 	}
+	/// Sets difficulty of computer opponent.
+	pub fn with_difficulty(mut self, difficulty: Difficulty) -> Self {
+		self.difficulty = difficulty;
+		self
+	}
+	/// Sets strategy build of computer opponent.
+	pub fn with_ai_build(mut self, ai_build: AIBuild) -> Self {
+		self.ai_build = Some(ai_build);
+		self
+	}
 }
 
 /// Game result for bot passed to [`on_end`](crate::Player::on_end).
@@ -201,3 +215,13 @@ impl FromProto<ProtoGameResult> for GameResult {
 		}
 	}
 }
+impl fmt::Display for GameResult {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			GameResult::Victory => "Victory",
+			GameResult::Defeat => "Defeat",
+			GameResult::Tie => "Tie",
+			GameResult::Undecided => "No result",
+		})
+	}
+}
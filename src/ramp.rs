@@ -19,6 +19,19 @@ pub struct Ramps {
 	pub enemy: Ramp,
 }
 
+impl Ramps {
+	/// Returns ramp closest to given position (e.g. location of expansion)
+	/// or `None` if there're no ramps in 15 tiles from it.
+	pub fn at(&self, pos: Point2) -> Option<&Ramp> {
+		self.all
+			.iter()
+			.filter_map(|r| Some((r, r.distance_to(pos)?)))
+			.filter(|(_, dist)| *dist <= 15.0)
+			.min_by(|(_, d1), (_, d2)| d1.partial_cmp(d2).unwrap())
+			.map(|(r, _)| r)
+	}
+}
+
 type Pos = (usize, usize);
 
 /// Ramp data structure with some helpful methods.
@@ -38,6 +51,20 @@ impl Ramp {
 			start_location,
 		}
 	}
+	/// Checks if given point is on the ramp.
+	pub fn contains_point(&self, p: Point2) -> bool {
+		let pos: Pos = p.into();
+		self.points.contains(&pos)
+	}
+	/// Returns distance from given position to the closest point of the ramp
+	/// or `None` if ramp has no points.
+	pub fn distance_to(&self, pos: Point2) -> Option<f32> {
+		self.points
+			.iter()
+			.map(|p| pos.distance_squared(Point2::from(*p)))
+			.min_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+			.map(f32::sqrt)
+	}
 	/// Returns only upper points of the ramp.
 	pub fn upper(&self) -> Vec<Pos> {
 		let mut max = u8::MIN;
@@ -0,0 +1,108 @@
+//! Helpers to construct game data and units in unit tests without running the game.
+
+use crate::{
+	bot::Rs,
+	game_data::{AbilityData, AbilityTarget, GameData, UnitTypeData},
+	geometry::{Point2, Point3},
+	ids::{AbilityId, UnitTypeId},
+	pixel_map::{Visibility, VisibilityMap},
+	player::Race,
+	unit::{DataForUnit, SharedUnitData, Unit},
+	IntoProto,
+};
+use ndarray::Array2;
+use num_traits::ToPrimitive;
+use sc2_proto::raw::{Alliance as ProtoAlliance, DisplayType as ProtoDisplayType, Unit as ProtoUnit};
+
+/// Size of the maps used in tests.
+pub(crate) const MAP_SIZE: usize = 64;
+
+/// Unit type data with given id and everything else empty.
+pub(crate) fn unit_type(id: UnitTypeId, race: Race) -> UnitTypeData {
+	UnitTypeData {
+		id,
+		name: format!("{:?}", id),
+		available: true,
+		cargo_size: 0,
+		mineral_cost: 0,
+		vespene_cost: 0,
+		food_required: 0.0,
+		food_provided: 0.0,
+		ability: None,
+		race,
+		build_time: 0.0,
+		has_vespene: false,
+		has_minerals: false,
+		sight_range: 0.0,
+		tech_alias: Vec::new(),
+		unit_alias: None,
+		tech_requirement: None,
+		require_attached: false,
+		attributes: Vec::new(),
+		movement_speed: 0.0,
+		armor: 0,
+		weapons: Vec::new(),
+	}
+}
+
+/// Ability data with given id and everything else empty.
+pub(crate) fn ability(id: AbilityId) -> AbilityData {
+	AbilityData {
+		id,
+		link_name: format!("{:?}", id),
+		link_index: 0,
+		button_name: None,
+		friendly_name: None,
+		hotkey: None,
+		remaps_to_ability_id: None,
+		available: true,
+		target: AbilityTarget::None,
+		allow_minimap: false,
+		allow_autocast: false,
+		is_building: false,
+		footprint_radius: None,
+		is_instant_placement: false,
+		cast_range: None,
+		energy_cost: None,
+	}
+}
+
+/// Builds game data from given unit types and abilities.
+pub(crate) fn game_data(units: Vec<UnitTypeData>, abilities: Vec<AbilityData>) -> GameData {
+	GameData {
+		units: units.into_iter().map(|data| (data.id, data)).collect(),
+		abilities: abilities.into_iter().map(|data| (data.id, data)).collect(),
+		..Default::default()
+	}
+}
+
+/// Data shared by all units in test with given game data.
+pub(crate) fn shared_data(game_data: GameData) -> SharedUnitData {
+	Rs::new(DataForUnit {
+		game_data: Rs::new(game_data),
+		..Default::default()
+	})
+}
+
+/// Fully visible map of [`MAP_SIZE`].
+pub(crate) fn visible_map() -> VisibilityMap {
+	Array2::from_elem((MAP_SIZE, MAP_SIZE), Visibility::Visible)
+}
+
+/// Own visible unit of given type at given position.
+pub(crate) fn proto_unit(tag: u64, type_id: UnitTypeId, pos: Point2) -> ProtoUnit {
+	let mut u = ProtoUnit::new();
+	u.set_display_type(ProtoDisplayType::Visible);
+	u.set_alliance(ProtoAlliance::value_Self);
+	u.set_tag(tag);
+	u.set_unit_type(type_id.to_u32().unwrap());
+	u.set_owner(1);
+	u.set_pos(Point3::new(pos.x, pos.y, 0.0).into_proto());
+	u.set_build_progress(1.0);
+	u
+}
+
+/// Builds unit from proto on fully visible map.
+pub(crate) fn unit(data: &SharedUnitData, u: &ProtoUnit) -> Unit {
+	Unit::from_proto(Rs::clone(data), &visible_map(), u)
+}
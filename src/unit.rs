@@ -5,8 +5,9 @@ use crate::{
 	action::{Commander, Target},
 	bot::{LockBool, LockOwned, LockU32, Locked, Reader, Rl, Rs, Rw},
 	consts::{
-		RaceValues, ANTI_ARMOR_BUFF, DAMAGE_BONUS_PER_UPGRADE, FRAMES_PER_SECOND, MISSED_WEAPONS,
-		OFF_CREEP_SPEED_UPGRADES, SPEED_BUFFS, SPEED_ON_CREEP, SPEED_UPGRADES, WARPGATE_ABILITIES,
+		RaceValues, ANTI_ARMOR_BUFF, AUTOCAST_ON_BY_DEFAULT, DAMAGE_BONUS_PER_UPGRADE, DEFAULT_TURN_RATE,
+		FRAMES_PER_SECOND, LAND_ABILITIES, LIFT_ABILITIES, MISSED_WEAPONS, OFF_CREEP_SPEED_UPGRADES,
+		SPEED_BUFFS, SPEED_ON_CREEP, SPEED_UPGRADES, SPLASH_RADIUS, TURN_RATES, WARPGATE_ABILITIES,
 	},
 	distance::Distance,
 	game_data::{Attribute, Cost, GameData, TargetType, UnitTypeData, Weapon},
@@ -15,7 +16,7 @@ use crate::{
 	ids::{AbilityId, BuffId, UnitTypeId, UpgradeId},
 	pixel_map::{PixelMap, VisibilityMap},
 	player::Race,
-	units::Container,
+	units::{Container, Units},
 	utils::CacheMap,
 	FromProto,
 };
@@ -27,6 +28,8 @@ use sc2_proto::raw::{
 	CloakState as ProtoCloakState, DisplayType as ProtoDisplayType, Unit as ProtoUnit,
 	UnitOrder_oneof_target as ProtoTarget,
 };
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 #[derive(Default, Clone)]
 pub(crate) struct DataForUnit {
@@ -408,6 +411,21 @@ impl Unit {
 	pub fn buff_duration_max(&self) -> Option<u32> {
 		self.base.buff_duration_max
 	}
+	/// How long (in seconds) a buff or unit is still around.
+	/// Converted from [`buff_duration_remain`](Self::buff_duration_remain) game loops.
+	///
+	/// Note: Not populated for enemies.
+	pub fn buff_time_remaining(&self) -> Option<f32> {
+		self.buff_duration_remain()
+			.map(|loops| loops as f32 / FRAMES_PER_SECOND)
+	}
+	/// Checks if a buff or unit will expire in less than given number of seconds.
+	///
+	/// Note: Always `false` for enemies, since buff duration isn't populated for them.
+	pub fn has_buff_expiring_soon(&self, threshold: f32) -> bool {
+		self.buff_time_remaining()
+			.map_or(false, |remaining| remaining > 0.0 && remaining < threshold)
+	}
 	/// All rally points of structure.
 	///
 	/// Note: Not populated for enemies.
@@ -430,6 +448,35 @@ impl Unit {
 	pub fn name(&self) -> &str {
 		self.type_data().map_or("", |data| &data.name)
 	}
+	/// Basic type of the unit, i.e. type without morphs or mode changes
+	/// (e.g. `SiegeTank` for `SiegeTankSieged`, `Zergling` for `ZerglingBurrowed`).
+	pub fn base_type(&self) -> UnitTypeId {
+		let type_id = self.type_id();
+		self.type_data()
+			.and_then(|data| data.unit_alias)
+			.filter(|alias| *alias != UnitTypeId::NotAUnit)
+			.unwrap_or(type_id)
+	}
+	fn tech_aliases(&self) -> &[UnitTypeId] {
+		self.type_data()
+			.map(|data| data.tech_alias.as_slice())
+			.unwrap_or_default()
+	}
+	/// Checks if unit has the same type as other unit considering unit and tech aliases
+	/// (e.g. `SiegeTank` and `SiegeTankSieged`, `Hatchery` and `Lair`,
+	/// `OrbitalCommand` and `PlanetaryFortress`).
+	///
+	/// Aliases are taken from game data and the check is symmetric.
+	pub fn is_same_as(&self, other: &Unit) -> bool {
+		let (base, other_base) = (self.base_type(), other.base_type());
+		if base == other_base {
+			return true;
+		}
+		let (aliases, other_aliases) = (self.tech_aliases(), other.tech_aliases());
+		aliases.contains(&other_base)
+			|| other_aliases.contains(&base)
+			|| aliases.iter().any(|alias| other_aliases.contains(alias))
+	}
 	/// Checks if unit is worker.
 	pub fn is_worker(&self) -> bool {
 		self.type_id().is_worker()
@@ -530,6 +577,24 @@ impl Unit {
 	pub fn race(&self) -> Race {
 		self.type_data().map_or(Race::Random, |data| data.race)
 	}
+	/// Checks if unit has enough energy to use given ability.
+	/// Returns `true` if ability doesn't require energy.
+	pub fn has_energy_for(&self, ability: AbilityId) -> bool {
+		match self.data.game_data.ability_energy_cost(ability) {
+			Some(cost) => self.energy().map_or(false, |energy| energy >= cost),
+			None => true,
+		}
+	}
+	/// Checks if unit is ready Nexus with enough energy to use chronoboost.
+	pub fn can_chrono(&self) -> bool {
+		self.type_id() == UnitTypeId::Nexus
+			&& self.is_ready()
+			&& self.has_energy_for(AbilityId::EffectChronoBoostEnergyCost)
+	}
+	/// Checks if unit is Queen with enough energy to inject larva.
+	pub fn can_inject(&self) -> bool {
+		self.type_id() == UnitTypeId::Queen && self.has_energy_for(AbilityId::EffectInjectLarva)
+	}
 	/// There're some units inside transport or bunker.
 	pub fn has_cargo(&self) -> bool {
 		self.cargo_space_taken().map_or(false, |taken| taken > 0)
@@ -559,10 +624,25 @@ impl Unit {
 			self.footprint_radius().map(|radius| (radius * 2.0) as usize)
 		}
 	}
-	/// How long a unit takes to build.
+	/// Returns grid tiles occupied by building, empty for non-structures.
+	///
+	/// Buildings of odd size (e.g. `3` for barracks) are centered on tile centers (`x.5`),
+	/// while buildings of even size (e.g. `2` for supply and addons) are centered on tile corners.
+	pub fn footprint(&self) -> Vec<(usize, usize)> {
+		if !self.is_structure() {
+			return Vec::new();
+		}
+		self.building_size()
+			.map_or_else(Vec::new, |size| footprint_tiles(self.position(), size).collect())
+	}
+	/// How long a unit takes to build (in game loops).
 	pub fn build_time(&self) -> f32 {
 		self.type_data().map_or(0.0, |data| data.build_time)
 	}
+	/// Game loops left until construction of unit is complete, `0` for ready units.
+	pub fn build_time_left(&self) -> f32 {
+		self.build_time() * (1.0 - self.build_progress())
+	}
 	/// Space that unit takes in transports and bunkers.
 	pub fn cargo_size(&self) -> u32 {
 		self.type_data().map_or(0, |data| data.cargo_size)
@@ -759,6 +839,29 @@ impl Unit {
 	pub fn distance_to_weapon_ready(&self) -> f32 {
 		self.real_speed() / FRAMES_PER_SECOND * self.weapon_cooldown().unwrap_or(0.0)
 	}
+	/// Turning rate of the unit in radians per game second.
+	///
+	/// Game data doesn't provide this value, so it's taken from internal table of slow-turning units.
+	/// Other units are considered to turn almost instantly.
+	pub fn turn_rate(&self) -> f32 {
+		TURN_RATES
+			.get(&self.type_id())
+			.copied()
+			.unwrap_or(DEFAULT_TURN_RATE)
+			.to_radians()
+	}
+	/// Approximate time in game seconds, needed to turn unit towards given point.
+	///
+	/// Doesn't consider acceleration and assumes unit turns with constant [`turn_rate`](Self::turn_rate).
+	pub fn turn_time_to(&self, target: Point2) -> f32 {
+		let direction = target - self.position();
+		if direction.len_squared() < f32::EPSILON {
+			return 0.0;
+		}
+		let angle = direction.y.atan2(direction.x) - self.facing();
+		let angle = angle.sin().atan2(angle.cos()).abs();
+		angle / self.turn_rate()
+	}
 	/// Attributes of unit, dependent on it's type.
 	pub fn attributes(&self) -> &[Attribute] {
 		self.type_data().map_or(&[], |data| data.attributes.as_slice())
@@ -800,6 +903,10 @@ impl Unit {
 	pub fn is_structure(&self) -> bool {
 		self.has_attribute(Attribute::Structure)
 	}
+	/// Checks if unit is terran structure lifted in the air.
+	pub fn is_lifted(&self) -> bool {
+		self.is_structure() && self.is_flying()
+	}
 	/// Checks if unit has `Hover` attribute.
 	pub fn is_hover(&self) -> bool {
 		self.has_attribute(Attribute::Hover)
@@ -843,6 +950,14 @@ impl Unit {
 	pub fn is_carrying_resource(&self) -> bool {
 		self.is_carrying_minerals() || self.is_carrying_vespene()
 	}
+	/// Checks if worker is heading home with resource.
+	///
+	/// Unlike [`is_carrying_resource`](Self::is_carrying_resource) uses [`is_returning`](Self::is_returning)
+	/// instead of buffs, so it also works with rich vespene geysers.
+	/// This is a reliable check for own workers, but doesn't work with enemies.
+	pub fn is_returning_with_cargo(&self) -> bool {
+		self.is_returning()
+	}
 
 	#[inline]
 	pub fn weapons(&self) -> &[Weapon] {
@@ -1232,49 +1347,13 @@ impl Unit {
 	/// [`real_air_range`]: Self::real_air_range
 	#[allow(clippy::mut_range_bound)]
 	pub fn calculate_weapon_stats(&self, target: CalcTarget) -> (f32, f32) {
-		let (upgrades, target_upgrades) = {
-			let my_upgrades = self.data.upgrades.read_lock();
-			let enemy_upgrades = self.data.enemy_upgrades.read_lock();
-			if self.is_mine() {
-				(my_upgrades, enemy_upgrades)
-			} else {
-				(enemy_upgrades, my_upgrades)
-			}
-		};
+		let upgrades = self.upgrades();
 
 		let (not_target, attributes, target_unit) = match target {
 			CalcTarget::Unit(target) => {
-				let mut enemy_armor = target.armor() + target.armor_upgrade_level();
-				let mut enemy_shield_armor = target.shield_upgrade_level();
-
-				let mut target_has_guardian_shield = false;
-
-				for buff in target.buffs() {
-					match buff {
-						BuffId::GuardianShield => target_has_guardian_shield = true,
-						_ => {
-							if *buff == ANTI_ARMOR_BUFF {
-								enemy_armor -= 3;
-								enemy_shield_armor -= 3;
-							}
-						}
-					}
-				}
-
-				if !target_upgrades.is_empty() {
-					if target.race().is_terran() {
-						if target.is_structure() && target_upgrades.contains(&UpgradeId::TerranBuildingArmor)
-						{
-							enemy_armor += 2;
-						}
-					} else if matches!(
-						target.type_id(),
-						UnitTypeId::Ultralisk | UnitTypeId::UltraliskBurrowed
-					) && target_upgrades.contains(&UpgradeId::ChitinousPlating)
-					{
-						enemy_armor += 2;
-					}
-				}
+				let enemy_armor = target.effective_armor();
+				let enemy_shield_armor = target.effective_shield_armor();
+				let target_has_guardian_shield = target.has_buff(BuffId::GuardianShield);
 
 				(
 					if matches!(target.type_id(), UnitTypeId::Colossus) {
@@ -1304,6 +1383,10 @@ impl Unit {
 			),
 		};
 
+		if self.type_id() == UnitTypeId::Bunker {
+			return self.cargo_weapon_stats(not_target);
+		}
+
 		let weapons = self.weapons();
 		if weapons.is_empty() {
 			return (0.0, 0.0);
@@ -1351,56 +1434,11 @@ impl Unit {
 			}
 		}
 
-		let damage_bonus_per_upgrade = DAMAGE_BONUS_PER_UPGRADE.get(&self.type_id());
 		let extract_weapon_stats = |w: &Weapon| {
-			let damage_bonus_per_upgrade = damage_bonus_per_upgrade.and_then(|bonus| bonus.get(&w.target));
-
-			let mut damage = w.damage
-				+ (self.attack_upgrade_level()
-					* damage_bonus_per_upgrade.and_then(|bonus| bonus.0).unwrap_or(1));
+			let damage = self.weapon_damage(w, attributes, &upgrades);
 			let speed = w.speed * speed_modifier;
 			let range = w.range + range_modifier;
 
-			// Bonus damage
-			if let Some(bonus) = w
-				.damage_bonus
-				.iter()
-				.filter_map(|(attribute, bonus)| {
-					if attributes.contains(attribute) {
-						let mut damage_bonus_per_upgrade = damage_bonus_per_upgrade
-							.and_then(|bonus| bonus.1.get(attribute))
-							.copied()
-							.unwrap_or(0);
-
-						if let Attribute::Light = attribute {
-							if upgrades.contains(&UpgradeId::HighCapacityBarrels) {
-								match self.type_id() {
-									UnitTypeId::Hellion => damage_bonus_per_upgrade += 5,
-									UnitTypeId::HellionTank => damage_bonus_per_upgrade += 12,
-									_ => {}
-								}
-							}
-						}
-
-						let mut bonus_damage =
-							bonus + (self.attack_upgrade_level() * damage_bonus_per_upgrade);
-
-						if let Attribute::Armored = attribute {
-							if self.has_buff(BuffId::VoidRaySwarmDamageBoost) {
-								bonus_damage += 6;
-							}
-						}
-
-						Some(bonus_damage)
-					} else {
-						None
-					}
-				})
-				.max_by(|b1, b2| b1.partial_cmp(b2).unwrap())
-			{
-				damage += bonus;
-			}
-
 			// Subtract damage
 			match target_unit {
 				Some((target, enemy_armor, enemy_shield_armor, target_has_guardian_shield)) => {
@@ -1463,6 +1501,195 @@ impl Unit {
 		};
 		(if speed == 0.0 { 0.0 } else { damage as f32 / speed }, range)
 	}
+	/// Returns damage of single attack of given weapon vs target with given attributes
+	/// including bonuses from upgrades and buffs, but without armor reduction.
+	fn weapon_damage(&self, w: &Weapon, attributes: &[Attribute], upgrades: &FxHashSet<UpgradeId>) -> u32 {
+		let damage_bonus_per_upgrade = DAMAGE_BONUS_PER_UPGRADE
+			.get(&self.type_id())
+			.and_then(|bonus| bonus.get(&w.target));
+
+		let mut damage = w.damage
+			+ (self.attack_upgrade_level() * damage_bonus_per_upgrade.and_then(|bonus| bonus.0).unwrap_or(1));
+
+		// Bonus damage
+		if let Some(bonus) = w
+			.damage_bonus
+			.iter()
+			.filter_map(|(attribute, bonus)| {
+				if attributes.contains(attribute) {
+					let mut damage_bonus_per_upgrade = damage_bonus_per_upgrade
+						.and_then(|bonus| bonus.1.get(attribute))
+						.copied()
+						.unwrap_or(0);
+
+					if let Attribute::Light = attribute {
+						if upgrades.contains(&UpgradeId::HighCapacityBarrels) {
+							match self.type_id() {
+								UnitTypeId::Hellion => damage_bonus_per_upgrade += 5,
+								UnitTypeId::HellionTank => damage_bonus_per_upgrade += 12,
+								_ => {}
+							}
+						}
+					}
+
+					let mut bonus_damage = bonus + (self.attack_upgrade_level() * damage_bonus_per_upgrade);
+
+					if let Attribute::Armored = attribute {
+						if self.has_buff(BuffId::VoidRaySwarmDamageBoost) {
+							bonus_damage += 6;
+						}
+					}
+
+					Some(bonus_damage)
+				} else {
+					None
+				}
+			})
+			.max_by(|b1, b2| b1.partial_cmp(b2).unwrap())
+		{
+			damage += bonus;
+		}
+		damage
+	}
+	/// Returns (dps, range) of passengers' weapons combined, ignoring targets of given type.
+	/// Range includes bonus of bunker. Upgrades of passengers aren't considered.
+	fn cargo_weapon_stats(&self, not_target: TargetType) -> (f32, f32) {
+		const BUNKER_RANGE_BONUS: f32 = 1.0;
+
+		let units = &self.data.game_data.units;
+		self.passengers()
+			.iter()
+			.filter_map(|p| {
+				units
+					.get(&p.type_id)?
+					.weapons
+					.iter()
+					.filter(|w| not_target.is_any() || w.target != not_target)
+					.map(|w| (w.damage as f32 * w.attacks as f32 / w.speed, w.range))
+					.max_by(|(d1, _), (d2, _)| d1.partial_cmp(d2).unwrap())
+			})
+			.fold((0.0, 0.0), |(dps, range), (w_dps, w_range)| {
+				(dps + w_dps, range.max(w_range + BUNKER_RANGE_BONUS))
+			})
+	}
+	/// Returns combined dps of units inside bunker vs ground targets.
+	///
+	/// Note: Passengers aren't populated for enemies, so it's `0` for enemy bunkers.
+	pub fn cargo_weapon_dps(&self) -> f32 {
+		self.cargo_weapon_stats(TargetType::Air).0
+	}
+	/// Splash radius of unit's weapon or `None` if it doesn't deal splash damage.
+	///
+	/// Values are taken from [`SPLASH_RADIUS`](crate::consts::SPLASH_RADIUS).
+	pub fn splash_radius(&self) -> Option<f32> {
+		SPLASH_RADIUS.get(&self.type_id()).copied()
+	}
+	/// Estimates dps of unit vs given target, including splash damage dealt to `nearby` units.
+	///
+	/// Damage vs target is calculated with [`real_weapon_vs`](Self::real_weapon_vs)
+	/// and then multiplied by number of units in splash radius around the target which unit can attack.
+	/// Target itself shouldn't be in `nearby`, if it is, it's counted once.
+	/// Falls back to single target dps for units without splash.
+	pub fn splash_damage_vs(&self, target: &Unit, nearby: &Units) -> f32 {
+		let dps = self.real_weapon_vs(target).0;
+		if dps == 0.0 {
+			return 0.0;
+		}
+		let radius = match self.splash_radius() {
+			Some(radius) => radius,
+			None => return dps,
+		};
+
+		let position = target.position();
+		let hits = nearby
+			.iter()
+			.filter(|u| {
+				u.tag() != target.tag()
+					&& u.is_flying() == target.is_flying()
+					&& self.can_attack_unit(u)
+					&& u.is_closer(radius + u.radius(), position)
+			})
+			.count();
+
+		dps * (hits + 1) as f32
+	}
+
+	/// Returns actual armor of unit including upgrades and buffs.
+	///
+	/// Considers anti-armor missile, Neosteel Armor for terran structures
+	/// and Chitinous Plating for ultralisks. Can be negative.
+	pub fn effective_armor(&self) -> i32 {
+		let mut armor = self.armor() + self.armor_upgrade_level();
+
+		if self.has_buff(ANTI_ARMOR_BUFF) {
+			armor -= 3;
+		}
+
+		let upgrades = self.upgrades();
+		if !upgrades.is_empty() {
+			if self.race().is_terran() {
+				if self.is_structure() && upgrades.contains(&UpgradeId::TerranBuildingArmor) {
+					armor += 2;
+				}
+			} else if matches!(
+				self.type_id(),
+				UnitTypeId::Ultralisk | UnitTypeId::UltraliskBurrowed
+			) && upgrades.contains(&UpgradeId::ChitinousPlating)
+			{
+				armor += 2;
+			}
+		}
+
+		armor
+	}
+	/// Returns actual shield armor of unit including upgrades and buffs.
+	///
+	/// Considers anti-armor missile. Can be negative.
+	pub fn effective_shield_armor(&self) -> i32 {
+		let mut shield_armor = self.shield_upgrade_level();
+
+		if self.has_buff(ANTI_ARMOR_BUFF) {
+			shield_armor -= 3;
+		}
+
+		shield_armor
+	}
+	/// Returns amount of damage attacker needs to deal (before armor reduction) to kill the unit.
+	///
+	/// Takes into account armor and shield armor of unit against damage per hit of attacker's weapon,
+	/// so can be used to rank targets by their actual durability.
+	/// Returns `f32::INFINITY` if attacker can't attack the unit and `0` if hits are unknown.
+	pub fn effective_hp_vs(&self, attacker: &Unit) -> f32 {
+		let weapon = if self.type_id() == UnitTypeId::Colossus {
+			attacker.weapons().first()
+		} else {
+			let not_target = if self.is_flying() {
+				TargetType::Ground
+			} else {
+				TargetType::Air
+			};
+			attacker.weapons().iter().find(|w| w.target != not_target)
+		};
+		let weapon = match weapon {
+			Some(weapon) => weapon,
+			None => return f32::INFINITY,
+		};
+
+		let damage = attacker.weapon_damage(weapon, self.attributes(), &attacker.upgrades()) as f32;
+
+		let mut armor = self.effective_armor();
+		let mut shield_armor = self.effective_shield_armor();
+		if self.has_buff(BuffId::GuardianShield) && weapon.range >= 2.0 {
+			armor += 2;
+			shield_armor += 2;
+		}
+
+		// Armor can't reduce damage below 0.5
+		let effective = |hits: u32, armor: i32| hits as f32 * damage / (damage - armor as f32).max(0.5);
+		self.shield()
+			.map_or(0.0, |shield| effective(shield, shield_armor))
+			+ self.health().map_or(0.0, |health| effective(health, armor))
+	}
 
 	/// Checks if unit is close enough to attack given target.
 	///
@@ -1557,17 +1784,11 @@ impl Unit {
 	}
 	/// Returns target point of unit's order if any.
 	pub fn target_pos(&self) -> Option<Point2> {
-		match self.target() {
-			Target::Pos(pos) => Some(pos),
-			_ => None,
-		}
+		self.target().as_pos()
 	}
 	/// Returns target tag of unit's order if any.
 	pub fn target_tag(&self) -> Option<u64> {
-		match self.target() {
-			Target::Tag(tag) => Some(tag),
-			_ => None,
-		}
+		self.target().as_tag()
 	}
 	/// Returns ability of first unit's order.
 	pub fn ordered_ability(&self) -> Option<AbilityId> {
@@ -1768,13 +1989,39 @@ impl Unit {
 
 	/// Toggles autocast on given ability.
 	pub fn toggle_autocast(&self, ability: AbilityId) {
+		let mut commander = self.data.commander.write_lock();
+		let state = commander
+			.autocast_state
+			.entry(self.tag())
+			.or_default()
+			.entry(ability)
+			.or_insert_with(|| AUTOCAST_ON_BY_DEFAULT.contains(&ability));
+		*state = !*state;
+		commander.autocast.entry(ability).or_default().push(self.tag());
+	}
+	/// Turns autocast on given ability on or off.
+	///
+	/// API can only toggle autocast, so this method tracks state of it
+	/// and toggles only when it differs from desired state.
+	pub fn set_autocast(&self, ability: AbilityId, on: bool) {
+		if self.is_autocast_on(ability) != on {
+			self.toggle_autocast(ability);
+		}
+	}
+	/// Checks if autocast on given ability is turned on.
+	///
+	/// State isn't provided by API, so it's tracked from bot's toggles
+	/// with assumption that only few abilities are autocasted by default.
+	/// Manual toggles made outside of the bot aren't detected.
+	pub fn is_autocast_on(&self, ability: AbilityId) -> bool {
 		self.data
 			.commander
-			.write_lock()
-			.autocast
-			.entry(ability)
-			.or_default()
-			.push(self.tag());
+			.read_lock()
+			.autocast_state
+			.get(&self.tag())
+			.and_then(|states| states.get(&ability))
+			.copied()
+			.unwrap_or_else(|| AUTOCAST_ON_BY_DEFAULT.contains(&ability))
 	}
 	/// Orders unit to execute given command.
 	pub fn command(&self, ability: AbilityId, target: Target, queue: bool) {
@@ -1826,6 +2073,14 @@ impl Unit {
 	pub fn repair(&self, target: u64, queue: bool) {
 		self.command(AbilityId::EffectRepair, Target::Tag(target), queue)
 	}
+	/// Orders Nexus to use chronoboost on structure with given tag.
+	pub fn chronoboost(&self, target: u64, queue: bool) {
+		self.command(AbilityId::EffectChronoBoostEnergyCost, Target::Tag(target), queue)
+	}
+	/// Orders Queen to inject larva into hatchery with given tag.
+	pub fn inject(&self, target: u64, queue: bool) {
+		self.command(AbilityId::EffectInjectLarva, Target::Tag(target), queue)
+	}
 	/// Orders building which is in progress to cancel construction.
 	pub fn cancel_building(&self, queue: bool) {
 		self.command(AbilityId::CancelBuildInProgress, Target::None, queue)
@@ -1894,12 +2149,24 @@ impl Unit {
 		}
 	}
 	/// Orders terran building to lift in the air.
+	///
+	/// Building specific ability is taken from [`LIFT_ABILITIES`](crate::consts::LIFT_ABILITIES).
 	pub fn lift(&self, queue: bool) {
-		self.command(AbilityId::Lift, Target::None, queue);
+		let ability = LIFT_ABILITIES
+			.get(&self.type_id())
+			.copied()
+			.unwrap_or(AbilityId::Lift);
+		self.command(ability, Target::None, queue);
 	}
 	/// Orders flying terran building to land on given position.
+	///
+	/// Building specific ability is taken from [`LAND_ABILITIES`](crate::consts::LAND_ABILITIES).
 	pub fn land(&self, target: Point2, queue: bool) {
-		self.command(AbilityId::Land, Target::Pos(target), queue);
+		let ability = LAND_ABILITIES
+			.get(&self.type_id())
+			.copied()
+			.unwrap_or(AbilityId::Land);
+		self.command(ability, Target::Pos(target), queue);
 	}
 }
 
@@ -2062,6 +2329,7 @@ impl Unit {
 /// Can be accessed through [`display_type`](Unit::display_type) field.
 #[variant_checkers]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DisplayType {
 	/// Fully visible.
 	Visible,
@@ -2118,6 +2386,18 @@ pub struct RallyTarget {
 	pub tag: Option<u64>,
 }
 
+/// Lightweight copy of unit's state on previous step.
+/// All snapshots stored in [`previous_units`](crate::bot::Bot::previous_units).
+#[derive(Debug, Clone, Copy)]
+pub struct UnitSnapshot {
+	/// Type of unit.
+	pub type_id: UnitTypeId,
+	/// Position of unit.
+	pub position: Point2,
+	/// Health + shield of unit, `None` if unknown.
+	pub hits: Option<u32>,
+}
+
 /// Trait for radius
 pub trait Radius {
 	/// Radius for struct
@@ -2136,3 +2416,218 @@ impl Radius for Unit {
 		self.radius()
 	}
 }
+
+pub(crate) fn footprint_tiles(pos: Point2, size: usize) -> impl Iterator<Item = (usize, usize)> {
+	let half = size as f32 / 2.0;
+	let x0 = (pos.x - half).round() as usize;
+	let y0 = (pos.y - half).round() as usize;
+	(x0..x0 + size).flat_map(move |x| (y0..y0 + size).map(move |y| (x, y)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_utils::{ability, game_data, proto_unit, shared_data, unit, unit_type};
+	use num_traits::ToPrimitive;
+	use sc2_proto::raw::PassengerUnit as ProtoPassengerUnit;
+
+	fn ground_weapon(damage: u32, damage_bonus: Vec<(Attribute, u32)>) -> Weapon {
+		Weapon {
+			target: TargetType::Ground,
+			damage,
+			damage_bonus,
+			attacks: 1,
+			range: 1.0,
+			speed: 1.0,
+		}
+	}
+
+	fn upgraded_unit(type_id: UnitTypeId, attack_level: i32) -> Unit {
+		let data = shared_data(game_data(vec![unit_type(type_id, Race::Random)], vec![]));
+		let mut u = proto_unit(1, type_id, Point2::new(10.5, 10.5));
+		u.set_attack_upgrade_level(attack_level);
+		unit(&data, &u)
+	}
+
+	#[test]
+	fn chitinous_plating_adds_ultralisk_armor() {
+		let mut ultralisk = unit_type(UnitTypeId::Ultralisk, Race::Zerg);
+		ultralisk.armor = 2;
+		let data = shared_data(game_data(vec![ultralisk], vec![]));
+		let mut u = proto_unit(1, UnitTypeId::Ultralisk, Point2::new(10.5, 10.5));
+		u.set_armor_upgrade_level(1);
+		let ultralisk = unit(&data, &u);
+
+		assert_eq!(ultralisk.effective_armor(), 3);
+		data.upgrades.write_lock().insert(UpgradeId::ChitinousPlating);
+		assert_eq!(ultralisk.effective_armor(), 5);
+	}
+
+	#[test]
+	fn attack_upgrades_add_damage_per_level() {
+		let upgrades = FxHashSet::default();
+
+		// Default bonus is 1 per level
+		let marine = upgraded_unit(UnitTypeId::Marine, 3);
+		assert_eq!(marine.weapon_damage(&ground_weapon(6, vec![]), &[], &upgrades), 9);
+
+		let ultralisk = upgraded_unit(UnitTypeId::Ultralisk, 3);
+		assert_eq!(
+			ultralisk.weapon_damage(&ground_weapon(35, vec![]), &[], &upgrades),
+			44
+		);
+
+		// Bonus vs attribute is upgraded only when target has it
+		let marauder = upgraded_unit(UnitTypeId::Marauder, 2);
+		let weapon = ground_weapon(10, vec![(Attribute::Armored, 10)]);
+		assert_eq!(
+			marauder.weapon_damage(&weapon, &[Attribute::Armored], &upgrades),
+			24
+		);
+		assert_eq!(
+			marauder.weapon_damage(&weapon, &[Attribute::Light], &upgrades),
+			12
+		);
+	}
+
+	fn structure(type_id: UnitTypeId, ability_id: AbilityId, footprint_radius: f32, pos: Point2) -> Unit {
+		let mut type_data = unit_type(type_id, Race::Terran);
+		type_data.attributes = vec![Attribute::Structure];
+		type_data.ability = Some(ability_id);
+		let mut ability_data = ability(ability_id);
+		ability_data.footprint_radius = Some(footprint_radius);
+		let data = shared_data(game_data(vec![type_data], vec![ability_data]));
+		unit(&data, &proto_unit(1, type_id, pos))
+	}
+
+	fn square(x: std::ops::Range<usize>, y: std::ops::Range<usize>) -> Vec<(usize, usize)> {
+		iproduct!(x, y).collect()
+	}
+
+	#[test]
+	fn footprint_of_odd_sized_structures() {
+		let barracks = structure(
+			UnitTypeId::Barracks,
+			AbilityId::TerranBuildBarracks,
+			1.5,
+			Point2::new(10.5, 10.5),
+		);
+		assert_eq!(barracks.building_size(), Some(3));
+		assert_eq!(barracks.footprint(), square(9..12, 9..12));
+
+		let command_center = structure(
+			UnitTypeId::CommandCenter,
+			AbilityId::TerranBuildCommandCenter,
+			2.5,
+			Point2::new(20.5, 20.5),
+		);
+		assert_eq!(command_center.building_size(), Some(5));
+		assert_eq!(command_center.footprint(), square(18..23, 18..23));
+	}
+
+	#[test]
+	fn footprint_of_even_sized_structures() {
+		let depot = structure(
+			UnitTypeId::SupplyDepot,
+			AbilityId::TerranBuildSupplyDepot,
+			1.0,
+			Point2::new(30.0, 30.0),
+		);
+		assert_eq!(depot.footprint(), square(29..31, 29..31));
+	}
+
+	#[test]
+	fn footprint_of_non_structures_is_empty() {
+		let data = shared_data(game_data(
+			vec![unit_type(UnitTypeId::Marine, Race::Terran)],
+			vec![],
+		));
+		let marine = unit(&data, &proto_unit(1, UnitTypeId::Marine, Point2::new(10.5, 10.5)));
+		assert!(marine.footprint().is_empty());
+	}
+
+	#[test]
+	fn bunker_weapon_is_combined_from_passengers() {
+		let mut marine = unit_type(UnitTypeId::Marine, Race::Terran);
+		marine.weapons = vec![Weapon {
+			target: TargetType::Any,
+			damage: 6,
+			damage_bonus: vec![],
+			attacks: 1,
+			range: 5.0,
+			speed: 0.61,
+		}];
+		let data = shared_data(game_data(
+			vec![unit_type(UnitTypeId::Bunker, Race::Terran), marine],
+			vec![],
+		));
+
+		let mut u = proto_unit(1, UnitTypeId::Bunker, Point2::new(10.5, 10.5));
+		let empty_bunker = unit(&data, &u);
+		assert_eq!(empty_bunker.cargo_weapon_dps(), 0.0);
+		assert_eq!(empty_bunker.real_ground_weapon(&[]), (0.0, 0.0));
+
+		for tag in 2..6 {
+			let mut passenger = ProtoPassengerUnit::new();
+			passenger.set_tag(tag);
+			passenger.set_unit_type(UnitTypeId::Marine.to_u32().unwrap());
+			u.mut_passengers().push(passenger);
+		}
+		u.set_cargo_space_taken(4);
+		u.set_cargo_space_max(4);
+		let bunker = unit(&data, &u);
+
+		let marine_dps = 6.0 / 0.61;
+		assert!((bunker.cargo_weapon_dps() - 4.0 * marine_dps).abs() < 1e-3);
+		for (dps, range) in [bunker.real_ground_weapon(&[]), bunker.real_air_weapon(&[])] {
+			assert!((dps - 4.0 * marine_dps).abs() < 1e-3);
+			assert_eq!(range, 6.0);
+		}
+	}
+
+	#[test]
+	fn is_same_as_is_symmetric() {
+		let with_alias = |id, unit_alias: Option<UnitTypeId>, tech_alias: Vec<UnitTypeId>| {
+			let mut data = unit_type(id, Race::Random);
+			data.unit_alias = unit_alias;
+			data.tech_alias = tech_alias;
+			data
+		};
+		let data = shared_data(game_data(
+			vec![
+				with_alias(UnitTypeId::SiegeTank, None, vec![]),
+				with_alias(UnitTypeId::SiegeTankSieged, Some(UnitTypeId::SiegeTank), vec![]),
+				with_alias(UnitTypeId::Hatchery, None, vec![]),
+				with_alias(UnitTypeId::Lair, None, vec![UnitTypeId::Hatchery]),
+				with_alias(
+					UnitTypeId::Hive,
+					None,
+					vec![UnitTypeId::Hatchery, UnitTypeId::Lair],
+				),
+				with_alias(UnitTypeId::OrbitalCommand, None, vec![UnitTypeId::CommandCenter]),
+				with_alias(
+					UnitTypeId::PlanetaryFortress,
+					None,
+					vec![UnitTypeId::CommandCenter],
+				),
+				with_alias(UnitTypeId::Marine, None, vec![]),
+				with_alias(UnitTypeId::Marauder, None, vec![]),
+			],
+			vec![],
+		));
+		let make = |type_id| unit(&data, &proto_unit(1, type_id, Point2::new(10.5, 10.5)));
+		let same = |a, b| {
+			let (a, b) = (make(a), make(b));
+			assert_eq!(a.is_same_as(&b), b.is_same_as(&a));
+			a.is_same_as(&b)
+		};
+
+		assert!(same(UnitTypeId::SiegeTank, UnitTypeId::SiegeTankSieged));
+		assert!(same(UnitTypeId::Hatchery, UnitTypeId::Lair));
+		assert!(same(UnitTypeId::Lair, UnitTypeId::Hive));
+		assert!(same(UnitTypeId::OrbitalCommand, UnitTypeId::PlanetaryFortress));
+		assert!(same(UnitTypeId::Marine, UnitTypeId::Marine));
+		assert!(!same(UnitTypeId::Marine, UnitTypeId::Marauder));
+		assert!(!same(UnitTypeId::SiegeTank, UnitTypeId::Hatchery));
+	}
+}
@@ -357,6 +357,18 @@ make_simple_iterator!(
 	|u| u.is_visible()
 );
 
+make_simple_iterator!(
+	/// An iterator that filters out hallucinations.
+	Real,
+	|u| !u.is_hallucination()
+);
+
+make_simple_iterator!(
+	/// An iterator that filters out snapshots.
+	NoSnapshots,
+	|u| !u.is_snapshot()
+);
+
 /// An iterator that filters units in attack range of given unit.
 #[derive(Clone)]
 pub struct InRangeOf<'a, I> {
@@ -504,6 +516,16 @@ where
 	fn visible(self) -> Visible<Self> {
 		Visible::new(self)
 	}
+	/// Excludes hallucinations.
+	///
+	/// Note: Hallucination flag isn't populated for snapshots.
+	fn real(self) -> Real<Self> {
+		Real::new(self)
+	}
+	/// Excludes snapshots (i.e. units hidden in fog of war or on high ground).
+	fn no_snapshots(self) -> NoSnapshots<Self> {
+		NoSnapshots::new(self)
+	}
 	/// Leaves only units in attack range of given unit.
 	fn in_range_of(self, unit: &Unit, gap: f32) -> InRangeOf<Self> {
 		InRangeOf::new(self, unit, gap)
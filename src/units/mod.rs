@@ -1,12 +1,18 @@
 //! Data structures for storing units, fast filtering and finding ones that needed.
 #![warn(missing_docs)]
 
-use crate::{geometry::Point2, ids::UnitTypeId, unit::Unit};
+use crate::{
+	geometry::Point2,
+	ids::UnitTypeId,
+	unit::Unit,
+	utils::{dbscan, range_query},
+};
 use indexmap::{
 	map::{Iter, IterMut, Keys, Values, ValuesMut},
 	IndexMap, IndexSet,
 };
 use iter::IntoUnits;
+use rand::{seq::index::sample, thread_rng, Rng};
 use rustc_hash::FxHasher;
 use std::{
 	hash::BuildHasherDefault,
@@ -74,6 +80,8 @@ pub struct PlayerUnits {
 	pub townhalls: Units,
 	/// Workers only (doesn't include MULEs).
 	pub workers: Units,
+	/// MULEs only (Populated for terrans only).
+	pub mules: Units,
 	/// The gas buildings on geysers used to gather gas.
 	pub gas_buildings: Units,
 	/// Most of zerg units are morphed from it (Populated for zergs only).
@@ -88,10 +96,26 @@ impl PlayerUnits {
 		self.structures.clear();
 		self.townhalls.clear();
 		self.workers.clear();
+		self.mules.clear();
 		self.gas_buildings.clear();
 		self.larvas.clear();
 		self.placeholders.clear();
 	}
+
+	/// Returns workers with no orders.
+	pub fn idle_workers(&self) -> Units {
+		self.workers.idle()
+	}
+	/// Returns workers currently gathering or returning resources.
+	///
+	/// Doesn't work with enemies.
+	pub fn gathering_workers(&self) -> Units {
+		self.workers.filter(|u| u.is_collecting())
+	}
+	/// Returns number of MULEs (these are not included in [`workers`](Self::workers)).
+	pub fn mule_count(&self) -> usize {
+		self.mules.len()
+	}
 }
 
 /// Convinient [`Unit`]s collection.
@@ -141,6 +165,29 @@ impl Units {
 		self.0.values().next()
 	}
 
+	/// Returns random unit from the collection.
+	///
+	/// Returns `None` if the collection is empty.
+	pub fn random(&self) -> Option<&Unit> {
+		if self.is_empty() {
+			return None;
+		}
+		self.0
+			.get_index(thread_rng().gen_range(0..self.len()))
+			.map(|i| i.1)
+	}
+
+	/// Returns `n` random units from the collection without repetitions.
+	///
+	/// If `n` is greater than length of the collection, returns all units in random order.
+	pub fn random_n(&self, n: usize) -> Vec<&Unit> {
+		let len = self.len();
+		sample(&mut thread_rng(), len, n.min(len))
+			.into_iter()
+			.filter_map(|i| self.0.get_index(i).map(|i| i.1))
+			.collect()
+	}
+
 	/// Inserts unit in the collection.
 	///
 	/// If collection already contains unit with the same tag,
@@ -166,6 +213,18 @@ impl Units {
 		self.0.remove(&u)
 	}
 
+	/// Removes all units matching given predicate from the collection and returns them.
+	///
+	/// Order of units is preserved in both collections.
+	pub fn extract<F>(&mut self, mut f: F) -> Self
+	where
+		F: FnMut(&Unit) -> bool,
+	{
+		let (extracted, remaining) = std::mem::take(&mut self.0).into_iter().partition(|(_, u)| f(u));
+		self.0 = remaining;
+		Self(extracted)
+	}
+
 	/// Returns an iterator over the units of the collection.
 	#[inline]
 	pub fn iter(&self) -> Values<u64, Unit> {
@@ -412,6 +471,30 @@ impl Units {
 	pub fn visible(&self) -> Self {
 		self.filter(|u| u.is_visible())
 	}
+	/// Excludes hallucinations and makes new collection of remaining units.
+	///
+	/// Note: Hallucination flag isn't populated for snapshots,
+	/// combine with [`no_snapshots`](Self::no_snapshots) to exclude them too.
+	///
+	/// Warning: This method will clone units in order to create a new collection
+	/// and will be evaluated initially. When applicable prefer using [`real`]
+	/// on the iterator over units, since it's lazily evaluated and doesn't do any cloning operations.
+	///
+	/// [`real`]: UnitsIterator::real
+	pub fn real(&self) -> Self {
+		self.filter(|u| !u.is_hallucination())
+	}
+	/// Excludes snapshots (i.e. units hidden in fog of war or on high ground)
+	/// and makes new collection of remaining units.
+	///
+	/// Warning: This method will clone units in order to create a new collection
+	/// and will be evaluated initially. When applicable prefer using [`no_snapshots`]
+	/// on the iterator over units, since it's lazily evaluated and doesn't do any cloning operations.
+	///
+	/// [`no_snapshots`]: UnitsIterator::no_snapshots
+	pub fn no_snapshots(&self) -> Self {
+		self.filter(|u| !u.is_snapshot())
+	}
 
 	/// Sorts the collection by given function.
 	pub fn sort<T, F>(&mut self, f: F)
@@ -432,6 +515,69 @@ impl Units {
 		sorted.0.sort_by(cmp_by2(f));
 		sorted
 	}
+
+	/// Returns unit with the lowest [`hits`](Unit::hits) (health + shield).
+	/// Units with unknown hits (i.e. snapshots) are considered to have maximum hits.
+	pub fn lowest_health(&self) -> Option<&Unit> {
+		self.iter().min_by_key(|u| u.hits().unwrap_or(u32::MAX))
+	}
+	/// Makes new collection sorted by [`hits`](Unit::hits) (health + shield) in ascending order.
+	/// Units with unknown hits (i.e. snapshots) are placed at the end.
+	/// Leaves original collection untouched.
+	pub fn sorted_by_health(&self) -> Self {
+		self.sorted(|u| u.hits().unwrap_or(u32::MAX))
+	}
+
+	/// Makes new collection of units contained in either `self` or `other`.
+	/// Units from `other` replace ones with the same tags.
+	pub fn union(&self, other: &Units) -> Self {
+		let mut union = self.clone();
+		union.extend(other.iter().cloned());
+		union
+	}
+	/// Makes new collection of units contained in `self`, but not in `other`.
+	pub fn difference(&self, other: &Units) -> Self {
+		self.filter(|u| !other.contains_tag(u.tag()))
+	}
+	/// Makes new collection of units contained in both `self` and `other`.
+	pub fn intersection(&self, other: &Units) -> Self {
+		self.filter(|u| other.contains_tag(u.tag()))
+	}
+
+	/// Splits the collection into clusters of units using [`dbscan`](crate::utils::dbscan).
+	///
+	/// Takes:
+	/// - `radius`: maximum distance between neighbor units.
+	/// - `min_points`: minimum neighbors (including unit itself) required for unit to not be marked as noise.
+	///
+	/// Returns: (Clusters, Noise).
+	pub fn dbscan(&self, radius: f32, min_points: usize) -> (Vec<Self>, Self) {
+		let tags = self.tags().copied().collect::<Vec<u64>>();
+		let (clusters, noise) = dbscan(
+			&tags,
+			range_query(
+				&tags,
+				|t1, t2| self[*t1].distance_squared(&self[*t2]),
+				radius * radius,
+			),
+			min_points,
+		);
+		(
+			clusters
+				.into_iter()
+				.map(|cluster| self.find_tags(&cluster))
+				.collect(),
+			self.find_tags(&noise),
+		)
+	}
+	/// Splits the collection into clusters of units using [`dbscan`](Self::dbscan).
+	///
+	/// Returns clusters sorted by size in descending order, noise units are not included.
+	pub fn cluster(&self, radius: f32, min_points: usize) -> Vec<Self> {
+		let mut clusters = self.dbscan(radius, min_points).0;
+		clusters.sort_unstable_by(|c1, c2| c2.len().cmp(&c1.len()));
+		clusters
+	}
 }
 
 impl FromIterator<Unit> for Units {
@@ -1,8 +1,13 @@
 //! Different utilites useful (or useless) in bot development.
 
 use indexmap::IndexSet;
+use ndarray::Array2;
 use rustc_hash::{FxHashMap, FxHashSet, FxHasher};
-use std::hash::{BuildHasherDefault, Hash};
+use std::{
+	cmp::Reverse,
+	collections::BinaryHeap,
+	hash::{BuildHasherDefault, Hash},
+};
 
 type FxIndexSet<T> = IndexSet<T, BuildHasherDefault<FxHasher>>;
 
@@ -80,10 +85,146 @@ where
 	}
 }
 
+type Tile = (usize, usize);
+
+const STRAIGHT: u32 = 10;
+const DIAGONAL: u32 = 14;
+const NEIGHBORS: [(isize, isize); 8] = [
+	(1, 0),
+	(0, 1),
+	(-1, 0),
+	(0, -1),
+	(1, 1),
+	(1, -1),
+	(-1, 1),
+	(-1, -1),
+];
+
+/// A* pathfinding on 2D grid.
+///
+/// Inputs:
+/// - `start`: tile to start from.
+/// - `goal`: tile to find path to.
+/// - `is_passable`: function that should return `true` if given tile can be walked through.
+///
+/// Diagonal moves are allowed, but not through corners of impassable tiles.
+/// Start and goal tiles are not required to be passable.
+///
+/// Returns: tiles of the shortest path including start and goal or `None` if goal is unreachable.
+pub fn astar<F>(start: Tile, goal: Tile, is_passable: F) -> Option<Vec<Tile>>
+where
+	F: Fn(Tile) -> bool,
+{
+	let heuristic = |(x, y): Tile| {
+		let dx = x.abs_diff(goal.0) as u32;
+		let dy = y.abs_diff(goal.1) as u32;
+		STRAIGHT * dx.max(dy) + (DIAGONAL - STRAIGHT) * dx.min(dy)
+	};
+
+	let mut open = BinaryHeap::new();
+	let mut costs = FxHashMap::<Tile, u32>::default();
+	let mut came_from = FxHashMap::<Tile, Tile>::default();
+	costs.insert(start, 0);
+	open.push(Reverse((heuristic(start), start)));
+
+	while let Some(Reverse((estimate, current))) = open.pop() {
+		if current == goal {
+			let mut path = vec![goal];
+			while let Some(prev) = came_from.get(path.last().unwrap()) {
+				path.push(*prev);
+			}
+			path.reverse();
+			return Some(path);
+		}
+
+		let cost = costs[&current];
+		// Skipping outdated entry
+		if estimate > cost + heuristic(current) {
+			continue;
+		}
+
+		let (x, y) = current;
+		for (dx, dy) in NEIGHBORS {
+			let (nx, ny) = (x as isize + dx, y as isize + dy);
+			if nx < 0 || ny < 0 {
+				continue;
+			}
+			let next = (nx as usize, ny as usize);
+			if next != goal && !is_passable(next) {
+				continue;
+			}
+			let diagonal = dx != 0 && dy != 0;
+			if diagonal && !(is_passable((next.0, y)) && is_passable((x, next.1))) {
+				continue;
+			}
+
+			let next_cost = cost + if diagonal { DIAGONAL } else { STRAIGHT };
+			if costs.get(&next).map_or(true, |c| next_cost < *c) {
+				costs.insert(next, next_cost);
+				came_from.insert(next, current);
+				open.push(Reverse((next_cost + heuristic(next), next)));
+			}
+		}
+	}
+	None
+}
+
+/// Dijkstra search from single tile over whole 2D grid.
+///
+/// Inputs:
+/// - `start`: tile to start from, not required to be passable.
+/// - `size`: size of the grid.
+/// - `is_passable`: function that should return `true` if given tile can be walked through.
+///
+/// Moves are the same as in [`astar`], straight step costs `10` and diagonal step costs `14`.
+///
+/// Returns: grid of path costs from start to every tile, `u32::MAX` for unreachable tiles.
+pub fn distance_field<F>(start: Tile, size: (usize, usize), is_passable: F) -> Array2<u32>
+where
+	F: Fn(Tile) -> bool,
+{
+	let mut costs = Array2::from_elem(size, u32::MAX);
+	let mut open = BinaryHeap::new();
+	if let Some(cost) = costs.get_mut(start) {
+		*cost = 0;
+		open.push(Reverse((0, start)));
+	}
+
+	while let Some(Reverse((cost, current))) = open.pop() {
+		// Skipping outdated entry
+		if cost > costs[current] {
+			continue;
+		}
+
+		let (x, y) = current;
+		for (dx, dy) in NEIGHBORS {
+			let (nx, ny) = (x as isize + dx, y as isize + dy);
+			if nx < 0 || ny < 0 {
+				continue;
+			}
+			let next = (nx as usize, ny as usize);
+			if !is_passable(next) {
+				continue;
+			}
+			let diagonal = dx != 0 && dy != 0;
+			if diagonal && !(is_passable((next.0, y)) && is_passable((x, next.1))) {
+				continue;
+			}
+
+			let next_cost = cost + if diagonal { DIAGONAL } else { STRAIGHT };
+			if let Some(c) = costs.get_mut(next).filter(|c| next_cost < **c) {
+				*c = next_cost;
+				open.push(Reverse((next_cost, next)));
+			}
+		}
+	}
+	costs
+}
+
 #[cfg(feature = "parking_lot")]
 use parking_lot::{RwLock, RwLockReadGuard};
 #[cfg(not(feature = "parking_lot"))]
-use std::sync::{RwLock, RwLockReadGuard};
+use std::sync::{PoisonError, RwLock, RwLockReadGuard};
 
 fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<T> {
 	#[cfg(feature = "parking_lot")]
@@ -94,6 +235,16 @@ fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<T> {
 	reader
 }
 
+fn get_mut<T>(lock: &mut RwLock<T>) -> &mut T {
+	#[cfg(feature = "parking_lot")]
+	let inner = lock.get_mut();
+	// Poisoned cache is still safe to clear, so poisoning is ignored
+	#[cfg(not(feature = "parking_lot"))]
+	let inner = lock.get_mut().unwrap_or_else(PoisonError::into_inner);
+
+	inner
+}
+
 #[derive(Default)]
 pub struct CacheMap<K, V>(RwLock<FxHashMap<K, V>>);
 impl<K, V> CacheMap<K, V>
@@ -124,4 +275,38 @@ where
 	pub fn get(&self, k: &K) -> Option<V> {
 		read(&self.0).get(k).copied()
 	}
+	pub fn clear(&mut self) {
+		get_mut(&mut self.0).clear();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn distance_field_goes_around_walls() {
+		// Wall at x = 2 with gap at y = 4
+		let is_passable = |(x, y): Tile| x < 5 && y < 5 && (x != 2 || y == 4);
+		let field = distance_field((0, 0), (5, 5), is_passable);
+
+		assert_eq!(field[(0, 0)], 0);
+		assert_eq!(field[(1, 1)], DIAGONAL);
+		assert_eq!(field[(1, 4)], 3 * STRAIGHT + DIAGONAL);
+		assert_eq!(field[(2, 4)], 4 * STRAIGHT + DIAGONAL);
+		// Corner of the wall can't be cut
+		assert_eq!(field[(3, 3)], 6 * STRAIGHT + DIAGONAL);
+		assert_eq!(field[(2, 0)], u32::MAX);
+	}
+
+	#[test]
+	fn cache_map_clear() {
+		let mut cache = CacheMap::<u32, u32>::default();
+		assert_eq!(cache.get_or_create(&1, || 10), 10);
+		assert_eq!(cache.get_or_create(&1, || 20), 10);
+
+		cache.clear();
+		assert_eq!(cache.get(&1), None);
+		assert_eq!(cache.get_or_create(&1, || 20), 20);
+	}
 }